@@ -0,0 +1,46 @@
+use crate::pong::Side;
+
+// cooldowns and the smash charge of a single paddle, times in seconds
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AbilityState {
+    pub dash_cooldown: f32,
+    // a dash only starts when the button is pressed, not while it is held
+    pub dash_held: bool,
+    // between `0.0` and `1.0` while the smash button is held
+    pub smash_charge: f32,
+    // time left to hit the ball with a released smash
+    pub smash_window: f32,
+    // charge of the released smash
+    pub smash_power: f32,
+    pub smash_cooldown: f32,
+}
+
+impl AbilityState {
+    pub fn smash_armed(&self) -> bool {
+        self.smash_window > 0.0
+    }
+}
+
+// ability state of both paddles as a `Resource`, updated by `AbilitySystem` and used up by
+// `BounceSystem` when a smash hits the ball
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Abilities {
+    pub left: AbilityState,
+    pub right: AbilityState,
+}
+
+impl Abilities {
+    pub fn get(&self, side: Side) -> &AbilityState {
+        match side {
+            Side::Left => &self.left,
+            Side::Right => &self.right,
+        }
+    }
+
+    pub fn get_mut(&mut self, side: Side) -> &mut AbilityState {
+        match side {
+            Side::Left => &mut self.left,
+            Side::Right => &mut self.right,
+        }
+    }
+}
@@ -1,27 +1,160 @@
-use amethyst::audio::AudioSink;
 use amethyst::{
-    assets::{AssetStorage, Loader},
-    audio::{output::Output, OggFormat, Source, SourceHandle},
+    assets::Loader,
+    audio::{OggFormat, SourceHandle},
     ecs::{World, WorldExt},
+    utils::application_root_dir,
 };
-use std::{iter::Cycle, vec::IntoIter};
+use log::warn;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::fs;
 
-const BOUNCE_SOUND: &str = "audio/bounce.ogg";
-const SCORE_SOUND: &str = "audio/score.ogg";
+use crate::sounds::{SfxOutput, SoundConfig, SoundRegistry};
 
-const MUSIC_TRACKS: &[&str] = &[
-    "audio/Computer_Music_All-Stars_-_Wheres_My_Jetpack.ogg",
-    "audio/Computer_Music_All-Stars_-_Albatross_v2.ogg",
-];
+// empty playlists play every `.ogg` file in this directory, relative to `assets`
+const MUSIC_DIR: &str = "audio/music";
 
-pub struct Sounds {
-    pub score_sfx: SourceHandle,
-    pub bounce_sfx: SourceHandle,
+// playlists read from `config/music.ron`, tracks are paths relative to `assets`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MusicConfig {
+    // played in the main menu and the screens on top of it
+    pub menu: Vec<String>,
+    // played during a match
+    pub game: Vec<String>,
+    // seconds it takes to fade from one track to the other when switching
+    pub crossfade: f32,
 }
 
+impl Default for MusicConfig {
+    fn default() -> Self {
+        Self {
+            menu: Vec::new(),
+            game: Vec::new(),
+            crossfade: 2.0,
+        }
+    }
+}
+
+// which playlist should be playing, chosen by the active state
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PlaylistKind {
+    Menu,
+    Match,
+}
+
+pub struct Playlist {
+    tracks: Vec<SourceHandle>,
+    // order the tracks are played in, shuffled again each time the playlist starts over
+    order: Vec<usize>,
+    // index into `order` of the current track, `None` until the first one is played
+    position: Option<usize>,
+}
+
+impl Playlist {
+    fn new(tracks: Vec<SourceHandle>) -> Self {
+        Self {
+            order: (0..tracks.len()).collect(),
+            tracks,
+            position: None,
+        }
+    }
+
+    // turning shuffle on or off takes effect the next time the playlist starts over
+    fn reorder(&mut self, shuffle: bool) {
+        self.order = (0..self.tracks.len()).collect();
+        if shuffle {
+            self.order.shuffle(&mut rand::thread_rng());
+        }
+    }
+
+    pub fn next(&mut self, shuffle: bool) -> Option<SourceHandle> {
+        if self.tracks.is_empty() {
+            return None;
+        }
+        let position = match self.position {
+            Some(position) if position + 1 < self.order.len() => position + 1,
+            _ => {
+                self.reorder(shuffle);
+                0
+            }
+        };
+        self.position = Some(position);
+        Some(self.tracks[self.order[position]].clone())
+    }
+
+    pub fn previous(&mut self) -> Option<SourceHandle> {
+        if self.tracks.is_empty() {
+            return None;
+        }
+        let position = match self.position {
+            Some(position) if position > 0 => position - 1,
+            _ => self.order.len() - 1,
+        };
+        self.position = Some(position);
+        Some(self.tracks[self.order[position]].clone())
+    }
+}
+
+// `Resource` with the loaded playlists, played by `MusicSystem`
 pub struct Music {
-    // use cycle to infinitely loop across tracks
-    pub music: Cycle<IntoIter<SourceHandle>>,
+    pub menu: Playlist,
+    pub game: Playlist,
+    // playlist the active state wants to hear, switching it crossfades to the other one
+    pub active: PlaylistKind,
+    pub crossfade: f32,
+}
+
+impl Music {
+    pub fn playlist_mut(&mut self, kind: PlaylistKind) -> &mut Playlist {
+        match kind {
+            PlaylistKind::Menu => &mut self.menu,
+            PlaylistKind::Match => &mut self.game,
+        }
+    }
+}
+
+// switches to the playlist of the active state, does nothing before the audio is initialised
+pub fn play_playlist(world: &mut World, kind: PlaylistKind) {
+    if let Some(mut music) = world.try_fetch_mut::<Music>() {
+        music.active = kind;
+    }
+}
+
+// every `.ogg` file inside `MUSIC_DIR`, in alphabetical order
+fn scan_music_dir() -> Vec<String> {
+    let dir = match application_root_dir() {
+        Ok(root) => root.join("assets").join(MUSIC_DIR),
+        Err(_) => return Vec::new(),
+    };
+    let mut tracks = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("ogg"))
+                .filter_map(|path| Some(format!("{}/{}", MUSIC_DIR, path.file_name()?.to_str()?)))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    tracks.sort();
+    tracks
+}
+
+fn load_playlist(loader: &Loader, world: &World, name: &str, tracks: &[String]) -> Playlist {
+    let tracks = if tracks.is_empty() {
+        scan_music_dir()
+    } else {
+        tracks.to_vec()
+    };
+    if tracks.is_empty() {
+        warn!("The {} playlist is empty, no music will be played", name);
+    }
+    Playlist::new(
+        tracks
+            .iter()
+            .map(|file| load_audio_track(loader, world, file))
+            .collect(),
+    )
 }
 
 // loads an `.ogg` audio track
@@ -29,27 +162,29 @@ fn load_audio_track(loader: &Loader, world: &World, file: &str) -> SourceHandle
     loader.load(file, OggFormat, (), &world.read_resource())
 }
 
-// initialise the audio in the world
+// initialise the audio in the world, unless it already is
 pub fn initialise_audio(world: &mut World) {
+    if world.has_value::<SoundRegistry>() {
+        return;
+    }
+
     let (sound_effects, music) = {
         let loader = world.read_resource::<Loader>();
+        let config = world.read_resource::<MusicConfig>();
 
-        let mut sink = world.write_resource::<AudioSink>();
-        sink.set_volume(0.25);
-
-        let music = MUSIC_TRACKS
-            .iter()
-            .map(|file| load_audio_track(&loader, &world, file))
-            .collect::<Vec<_>>()
-            .into_iter()
-            .cycle();
-        let music = Music { music };
-
-        let sound = Sounds {
-            bounce_sfx: load_audio_track(&loader, &world, BOUNCE_SOUND),
-            score_sfx: load_audio_track(&loader, &world, SCORE_SOUND),
+        let music = Music {
+            menu: load_playlist(&loader, world, "menu", &config.menu),
+            game: load_playlist(&loader, world, "match", &config.game),
+            active: PlaylistKind::Menu,
+            crossfade: config.crossfade,
         };
 
+        let sound = SoundRegistry::load(
+            &world.read_resource::<SoundConfig>(),
+            &loader,
+            &world.read_resource(),
+        );
+
         (sound, music)
     };
 
@@ -57,20 +192,7 @@ pub fn initialise_audio(world: &mut World) {
     // this needs to be in another scope as it cannot be done as long as `Loader` is borrowed
     world.insert(sound_effects);
     world.insert(music);
-}
-
-pub fn play_bounce_sound(sounds: &Sounds, storage: &AssetStorage<Source>, output: Option<&Output>) {
-    if let Some(ref output) = output.as_ref() {
-        if let Some(sound) = storage.get(&sounds.bounce_sfx) {
-            output.play_once(sound, 1.0);
-        }
-    }
-}
-
-pub fn play_score_sound(sounds: &Sounds, storage: &AssetStorage<Source>, output: Option<&Output>) {
-    if let Some(ref output) = output.as_ref() {
-        if let Some(sound) = storage.get(&sounds.score_sfx) {
-            output.play_once(sound, 1.0);
-        }
+    if let Some(output) = SfxOutput::new() {
+        world.insert(output);
     }
 }
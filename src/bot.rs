@@ -0,0 +1,334 @@
+use amethyst::Error;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{Shutdown, TcpStream},
+    process::{Child, Command, Stdio},
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::pong::Side;
+
+// bots connected over TCP are given as `tcp:HOST:PORT`, anything else is a command to run
+const TCP_PREFIX: &str = "tcp:";
+// requests waiting to be written to a bot, further ones are dropped while a bot does not read
+const WRITE_QUEUE: usize = 4;
+
+// a ball as sent to bots
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BotBall {
+    pub x: f32,
+    pub y: f32,
+    pub vx: f32,
+    pub vy: f32,
+}
+
+// a paddle as sent to bots, `x` and `y` are its centre
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BotPaddle {
+    pub side: Side,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+// the line sent to a bot every tick, in arena units with `(0, 0)` at the bottom left
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BotRequest {
+    pub tick: u64,
+    // the paddle the bot controls
+    pub side: Side,
+    pub arena: [f32; 2],
+    pub balls: Vec<BotBall>,
+    pub paddles: Vec<BotPaddle>,
+    pub score_left: i32,
+    pub score_right: i32,
+}
+
+// the line a bot answers with, a bare number is accepted as the axis as well
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BotReply {
+    Axis(f32),
+    // `tick` echoes the request, replies to older requests are skipped
+    Full { axis: f32, tick: Option<u64> },
+}
+
+// an external process or socket moving a paddle, speaking JSON Lines: it receives a `BotRequest`
+// every tick and replies with an axis value between `-1.0` and `1.0`
+pub struct BotConnection {
+    name: String,
+    // lines written by a background thread, so a bot that stops reading cannot block the game
+    requests: SyncSender<String>,
+    // lines read by a background thread, so waiting for them can time out
+    replies: Mutex<Receiver<String>>,
+    // the bot's process, stopped when the connection is dropped
+    child: Option<Child>,
+    // the bot's socket, shut down when the connection is dropped so the reader thread ends
+    stream: Option<TcpStream>,
+    connected: bool,
+}
+
+impl BotConnection {
+    // starts the command, or connects to the address given as `tcp:HOST:PORT`
+    pub fn open(spec: &str) -> Result<Self, Error> {
+        let fail =
+            |e: std::io::Error| Error::from_string(format!("Could not start bot {}: {}", spec, e));
+        if let Some(address) = spec.strip_prefix(TCP_PREFIX) {
+            let stream = TcpStream::connect(address).map_err(fail)?;
+            stream.set_nodelay(true).map_err(fail)?;
+            let reader = stream.try_clone().map_err(fail)?;
+            let writer = stream.try_clone().map_err(fail)?;
+            let mut connection = Self::new(spec, Box::new(writer), reader, None);
+            connection.stream = Some(stream);
+            Ok(connection)
+        } else {
+            let mut words = spec.split_whitespace();
+            let program = words
+                .next()
+                .ok_or_else(|| Error::from_string("Empty bot command".to_string()))?;
+            let mut child = Command::new(program)
+                .args(words)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .spawn()
+                .map_err(fail)?;
+            let stdin = child.stdin.take().expect("Bot stdin is piped");
+            let stdout = child.stdout.take().expect("Bot stdout is piped");
+            Ok(Self::new(spec, Box::new(stdin), stdout, Some(child)))
+        }
+    }
+
+    fn new(
+        name: &str,
+        writer: Box<dyn Write + Send>,
+        reader: impl Read + Send + 'static,
+        child: Option<Child>,
+    ) -> Self {
+        let (requests, pending) = mpsc::sync_channel::<String>(WRITE_QUEUE);
+        let writer_name = name.to_string();
+        thread::spawn(move || {
+            let mut writer = writer;
+            // stops when the bot disconnects or the connection is dropped
+            for line in pending {
+                if let Err(e) = writeln!(writer, "{}", line).and_then(|_| writer.flush()) {
+                    warn!("Bot {} disconnected: {}", writer_name, e);
+                    break;
+                }
+            }
+        });
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            // stops when the bot disconnects or the connection is dropped
+            for line in BufReader::new(reader).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        info!("Connected to bot {}", name);
+        Self {
+            name: name.to_string(),
+            requests,
+            replies: Mutex::new(receiver),
+            child,
+            stream: None,
+            connected: true,
+        }
+    }
+
+    // sends the state and waits up to `timeout` for the bot's axis, the paddle stays idle if the
+    // bot is too slow, answers nonsense or has disconnected
+    pub fn axis(&mut self, request: &BotRequest, timeout: Duration) -> f32 {
+        if !self.connected {
+            return 0.0;
+        }
+
+        let replies = self.replies.get_mut().expect("Bot reply lock poisoned");
+        // answers that arrived after their tick timed out are of no use anymore
+        while replies.try_recv().is_ok() {}
+
+        let line = serde_json::to_string(request).expect("Bot requests are valid JSON");
+        match self.requests.try_send(line) {
+            Ok(()) => {}
+            // the bot has not read the previous requests yet, so it cannot answer this one in time
+            Err(TrySendError::Full(_)) => return 0.0,
+            Err(TrySendError::Disconnected(_)) => {
+                self.connected = false;
+                return 0.0;
+            }
+        }
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let line = match replies.recv_timeout(remaining) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => return 0.0,
+                Err(RecvTimeoutError::Disconnected) => {
+                    warn!("Bot {} disconnected", self.name);
+                    self.connected = false;
+                    return 0.0;
+                }
+            };
+            match parse_reply(&line, request.tick) {
+                ParsedReply::Axis(axis) => return axis,
+                ParsedReply::Stale => {}
+                ParsedReply::Invalid(e) => {
+                    warn!("Invalid reply from bot {}: {:?} ({})", self.name, line, e);
+                    return 0.0;
+                }
+            }
+        }
+    }
+}
+
+// what a line from a bot means for the request of `tick`
+#[derive(Debug, PartialEq)]
+enum ParsedReply {
+    Axis(f32),
+    // a late answer to an earlier request
+    Stale,
+    Invalid(String),
+}
+
+fn parse_reply(line: &str, tick: u64) -> ParsedReply {
+    match serde_json::from_str::<BotReply>(line) {
+        Ok(BotReply::Axis(axis)) | Ok(BotReply::Full { axis, tick: None }) => {
+            ParsedReply::Axis(sanitize(axis))
+        }
+        Ok(BotReply::Full {
+            axis,
+            tick: Some(reply_tick),
+        }) if reply_tick == tick => ParsedReply::Axis(sanitize(axis)),
+        Ok(BotReply::Full { .. }) => ParsedReply::Stale,
+        Err(e) => ParsedReply::Invalid(e.to_string()),
+    }
+}
+
+fn sanitize(axis: f32) -> f32 {
+    if axis.is_finite() {
+        axis.clamp(-1.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+impl Drop for BotConnection {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        if let Some(stream) = &self.stream {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+// the bots moving the paddles with `Controller::Bot`, as a `Resource`
+pub struct Bots {
+    pub left: Option<BotConnection>,
+    pub right: Option<BotConnection>,
+    // how long to wait for a bot's answer every tick
+    pub timeout: Duration,
+    // ticks sent so far
+    pub tick: u64,
+}
+
+impl Default for Bots {
+    fn default() -> Self {
+        Self {
+            left: None,
+            right: None,
+            timeout: Duration::from_millis(20),
+            tick: 0,
+        }
+    }
+}
+
+impl Bots {
+    pub fn get_mut(&mut self, side: Side) -> Option<&mut BotConnection> {
+        match side {
+            Side::Left => self.left.as_mut(),
+            Side::Right => self.right.as_mut(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_numbers_and_objects_are_axis_values() {
+        assert_eq!(parse_reply("0.5", 7), ParsedReply::Axis(0.5));
+        assert_eq!(
+            parse_reply(r#"{"axis": -0.25}"#, 7),
+            ParsedReply::Axis(-0.25)
+        );
+        assert_eq!(
+            parse_reply(r#"{"axis": 1.0, "tick": 7}"#, 7),
+            ParsedReply::Axis(1.0)
+        );
+    }
+
+    #[test]
+    fn answers_to_earlier_ticks_are_stale() {
+        assert_eq!(
+            parse_reply(r#"{"axis": 1.0, "tick": 6}"#, 7),
+            ParsedReply::Stale
+        );
+    }
+
+    #[test]
+    fn out_of_range_axis_values_are_clamped() {
+        assert_eq!(parse_reply("3.5", 0), ParsedReply::Axis(1.0));
+        assert_eq!(parse_reply(r#"{"axis": -20}"#, 0), ParsedReply::Axis(-1.0));
+    }
+
+    #[test]
+    fn non_finite_axis_values_leave_the_paddle_idle() {
+        assert_eq!(sanitize(f32::NAN), 0.0);
+        assert_eq!(sanitize(f32::INFINITY), 0.0);
+        // JSON has no NaN, bots sending one send nonsense
+        assert!(matches!(parse_reply("NaN", 0), ParsedReply::Invalid(_)));
+    }
+
+    #[test]
+    fn nonsense_is_invalid() {
+        assert!(matches!(parse_reply("up", 0), ParsedReply::Invalid(_)));
+        assert!(matches!(
+            parse_reply(r#"{"tick": 0}"#, 0),
+            ParsedReply::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn dropping_a_socket_bot_closes_its_connection() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let bot = BotConnection::open(&format!("tcp:{}", address)).unwrap();
+        let (mut peer, _) = listener.accept().unwrap();
+        peer.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+        drop(bot);
+        // the bot's end sees the connection close instead of waiting for more requests
+        assert_eq!(peer.read(&mut [0; 16]).unwrap(), 0);
+    }
+
+    #[test]
+    fn bots_are_looked_up_by_side_and_wait_20_ms_by_default() {
+        let mut bots = Bots::default();
+        assert!(bots.get_mut(Side::Left).is_none());
+        assert!(bots.get_mut(Side::Right).is_none());
+        assert_eq!(bots.timeout, Duration::from_millis(20));
+    }
+}
@@ -0,0 +1,145 @@
+use amethyst::{
+    core::{bundle::SystemBundle, SystemDesc, SystemExt},
+    ecs::{DispatcherBuilder, System, SystemData, World},
+    Error,
+};
+
+use crate::{
+    pong::RunState,
+    sounds::SfxEnabled,
+    systems::{
+        AbilitySystem, BounceSystem, MoveBallsSystem, PaddleInputSystem, PaddleSystem,
+        TelemetrySystemDesc, WinnerSystem,
+    },
+};
+
+// the gameplay `System`s of a match: paddle input, abilities and movement, ball movement,
+// collisions, scoring and telemetry, all paused unless `RunState` is `Running`
+//
+// the input source `I` has to fill `PaddleInputs` and the scoring `W` has to update the
+// `ScoreBoard` and publish `GameEvent::Goal`s, they keep the names `"paddle_input_system"` and
+// `"winner_system"` whatever replaces them, so other `System`s can depend on them
+pub struct PongBundle<'a, I = PaddleInputSystem, W = WinnerSystem> {
+    dep: &'a [&'a str],
+    input: I,
+    scoring: W,
+    audio: bool,
+    telemetry: bool,
+}
+
+impl Default for PongBundle<'_> {
+    fn default() -> Self {
+        Self {
+            dep: &[],
+            input: PaddleInputSystem::default(),
+            scoring: WinnerSystem,
+            audio: true,
+            telemetry: true,
+        }
+    }
+}
+
+impl PongBundle<'_> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl<'a, I, W> PongBundle<'a, I, W> {
+    // `System`s the paddle input has to run after, such as `"input_system"`
+    pub fn with_dep(mut self, dep: &'a [&'a str]) -> Self {
+        self.dep = dep;
+        self
+    }
+
+    // replaces `PaddleInputSystem`, e.g. with one reading a network connection
+    pub fn with_input_source<J>(self, input: J) -> PongBundle<'a, J, W> {
+        PongBundle {
+            dep: self.dep,
+            input,
+            scoring: self.scoring,
+            audio: self.audio,
+            telemetry: self.telemetry,
+        }
+    }
+
+    // replaces `WinnerSystem`, e.g. with one scoring differently
+    pub fn with_scoring<X>(self, scoring: X) -> PongBundle<'a, I, X> {
+        PongBundle {
+            dep: self.dep,
+            input: self.input,
+            scoring,
+            audio: self.audio,
+            telemetry: self.telemetry,
+        }
+    }
+
+    // no sound effects are played, not even from states
+    pub fn without_audio(mut self) -> Self {
+        self.audio = false;
+        self
+    }
+
+    // leaves out `TelemetrySystem`
+    pub fn without_telemetry(mut self) -> Self {
+        self.telemetry = false;
+        self
+    }
+}
+
+impl<'a, 'b, 'c, I, W> SystemBundle<'a, 'b> for PongBundle<'c, I, W>
+where
+    I: for<'s> System<'s> + Send + 'a,
+    W: for<'s> System<'s> + Send + 'a,
+    // needed to pause them
+    for<'s> <I as System<'s>>::SystemData: SystemData<'s>,
+    for<'s> <W as System<'s>>::SystemData: SystemData<'s>,
+{
+    fn build(
+        self,
+        world: &mut World,
+        builder: &mut DispatcherBuilder<'a, 'b>,
+    ) -> Result<(), Error> {
+        world.insert(SfxEnabled(self.audio));
+
+        builder.add(
+            self.input.pausable(RunState::Running),
+            "paddle_input_system",
+            self.dep,
+        );
+        builder.add(
+            AbilitySystem.pausable(RunState::Running),
+            "ability_system",
+            &["paddle_input_system"],
+        );
+        builder.add(
+            PaddleSystem.pausable(RunState::Running),
+            "paddle_system",
+            &["ability_system"],
+        );
+        builder.add(
+            MoveBallsSystem.pausable(RunState::Running),
+            "ball_system",
+            &[],
+        );
+        builder.add(
+            BounceSystem.pausable(RunState::Running),
+            "collision_system",
+            &["paddle_system", "ball_system"],
+        );
+        builder.add(
+            self.scoring.pausable(RunState::Running),
+            "winner_system",
+            &["ball_system"],
+        );
+        if self.telemetry {
+            // pauses itself outside of a running match
+            builder.add(
+                TelemetrySystemDesc.build(world),
+                "telemetry_system",
+                &["collision_system", "winner_system"],
+            );
+        }
+        Ok(())
+    }
+}
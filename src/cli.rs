@@ -0,0 +1,160 @@
+use amethyst::{utils::application_root_dir, Error};
+use clap::Parser;
+use log::LevelFilter;
+use std::{path::PathBuf, time::Duration};
+
+use crate::{
+    bot::{BotConnection, Bots},
+    controls::{Controller, Controllers, Difficulty, GameMode},
+    pong::Side,
+    telemetry::Telemetry,
+};
+
+// command line arguments, available as a `Resource`, doc comments become the `--help` text
+#[derive(Clone, Debug, Parser)]
+#[command(about = "Pong, made with Amethyst")]
+pub struct Cli {
+    /// Who plays against whom
+    #[arg(long, value_enum, default_value = "versus")]
+    pub mode: GameMode,
+    /// How well computer controlled paddles play
+    #[arg(long, value_enum, default_value = "normal")]
+    pub ai: Difficulty,
+    /// Computer player of this difficulty on the left, whatever the mode
+    #[arg(long, value_enum, value_name = "AI")]
+    pub left_ai: Option<Difficulty>,
+    /// Computer player of this difficulty on the right, whatever the mode
+    #[arg(long, value_enum, value_name = "AI")]
+    pub right_ai: Option<Difficulty>,
+    /// Rules file used instead of `config/rules.ron`
+    #[arg(long, value_name = "FILE")]
+    pub rules: Option<PathBuf>,
+    /// Size of the window, e.g. `800x600`, instead of the one in `config/display.ron`
+    #[arg(long, value_name = "WIDTHxHEIGHT", value_parser = parse_window_size)]
+    pub window_size: Option<(u32, u32)>,
+    /// Plays a single match without a window or audio as fast as possible and prints the result
+    #[arg(long)]
+    pub headless: bool,
+    /// Plays this many headless matches between the paddles' controllers and reports the results
+    #[arg(long, value_name = "MATCHES", conflicts_with = "headless")]
+    pub tournament: Option<u32>,
+    /// Writes the tournament report as JSON to this file
+    #[arg(long, value_name = "FILE", requires = "tournament")]
+    pub report: Option<PathBuf>,
+    /// Plays back a replay saved at the end of a match instead of starting the game
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["headless", "tournament"])]
+    pub replay: Option<PathBuf>,
+    /// Writes match events to this file, as CSV if it ends in `.csv` and as JSON Lines otherwise,
+    /// tournaments have `--report` instead
+    #[arg(long, value_name = "FILE", conflicts_with = "tournament")]
+    pub telemetry: Option<PathBuf>,
+    /// Bot moving the left paddle instead of the mode's controller: a command speaking JSON Lines
+    /// over stdin and stdout, or `tcp:HOST:PORT` to connect to one listening on a socket
+    #[arg(long, value_name = "BOT")]
+    pub left_bot: Option<String>,
+    /// Bot moving the right paddle, as `--left-bot`
+    #[arg(long, value_name = "BOT")]
+    pub right_bot: Option<String>,
+    /// Milliseconds to wait for a bot's answer every tick before leaving its paddle idle, the
+    /// game waits on its main thread, so in windowed matches slow bots lower the frame rate by up
+    /// to this much per bot and frame
+    #[arg(long, value_name = "MS", default_value_t = 20)]
+    pub bot_timeout: u64,
+    /// Seed of every random gameplay decision, so matches can be repeated, tournaments seed their
+    /// matches from it, starting from `0` by default
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// One of `off`, `error`, `warn`, `info`, `debug` or `trace`
+    #[arg(long, default_value = "info")]
+    pub log_level: LevelFilter,
+}
+
+impl Cli {
+    // computer players and bots given for a side replace the controllers of the game mode
+    pub fn controllers(&self) -> Controllers {
+        let mut controllers = self.mode.controllers(self.ai);
+        if let Some(difficulty) = self.left_ai {
+            controllers.left = Controller::Ai(difficulty);
+        }
+        if let Some(difficulty) = self.right_ai {
+            controllers.right = Controller::Ai(difficulty);
+        }
+        if self.left_bot.is_some() {
+            controllers.left = Controller::Bot;
+        }
+        if self.right_bot.is_some() {
+            controllers.right = Controller::Bot;
+        }
+        controllers
+    }
+
+    // controllers of headless matches and tournaments, which nobody can play with the keyboard
+    pub fn headless_controllers(&self) -> Result<Controllers, Error> {
+        let controllers = self.controllers();
+        for (side, flag) in [(Side::Left, "left"), (Side::Right, "right")] {
+            if controllers.get(side) == Controller::Keyboard {
+                return Err(Error::from_string(format!(
+                    "The {} paddle is moved with the keyboard, which nobody can use without a \
+                     window, give it `--{}-ai` or `--{}-bot`, or use `--mode watch`",
+                    flag, flag, flag
+                )));
+            }
+        }
+        Ok(controllers)
+    }
+
+    // starts or connects to the bots given
+    pub fn bots(&self) -> Result<Bots, Error> {
+        let open = |spec: &Option<String>| spec.as_deref().map(BotConnection::open).transpose();
+        Ok(Bots {
+            left: open(&self.left_bot)?,
+            right: open(&self.right_bot)?,
+            timeout: Duration::from_millis(self.bot_timeout),
+            ..Default::default()
+        })
+    }
+
+    // name shown instead of the player profile for paddles not moved by a human, so their
+    // matches do not touch the ratings of human players
+    pub fn player_name(&self, side: Side) -> Option<String> {
+        let bot = match side {
+            Side::Left => &self.left_bot,
+            Side::Right => &self.right_bot,
+        };
+        match bot {
+            Some(spec) => Some(format!("Bot: {}", spec)),
+            None => self.controllers().get(side).ai_name(),
+        }
+    }
+
+    // disabled unless a file was given
+    pub fn telemetry(&self) -> Result<Telemetry, Error> {
+        match &self.telemetry {
+            Some(path) => Telemetry::create(path),
+            None => Ok(Telemetry::default()),
+        }
+    }
+
+    pub fn rules_path(&self) -> Result<PathBuf, Error> {
+        match &self.rules {
+            Some(path) => Ok(path.clone()),
+            None => Ok(application_root_dir()?.join("config").join("rules.ron")),
+        }
+    }
+}
+
+// parses sizes like `800x600`
+fn parse_window_size(value: &str) -> Result<(u32, u32), String> {
+    let (width, height) = value
+        .split_once('x')
+        .ok_or_else(|| "expected WIDTHxHEIGHT, e.g. 800x600".to_string())?;
+    let parse = |number: &str| {
+        number
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|number| *number > 0)
+            .ok_or_else(|| format!("invalid window dimension: {}", number))
+    };
+    Ok((parse(width)?, parse(height)?))
+}
@@ -0,0 +1,436 @@
+use amethyst::{
+    config::Config,
+    core::Transform,
+    ecs::{Entities, Entity, Join, ReadExpect, WriteStorage},
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::*,
+    ui::{Anchor, LineMode, UiImage, UiText, UiTransform},
+    winit::{Event, WindowEvent},
+};
+
+use crate::{
+    cli::Cli,
+    pong::{initialise_ball, Ball, GameSpeed, Paddle, ScoreBoard, ARENA_HEIGHT, ARENA_WIDTH},
+    rules::Rules,
+    systems::ScaledUi,
+    theme::{SpriteRole, ThemeAssets, Themed},
+    ui::{load_font, text_color},
+};
+
+// only the most recent lines of output fit into the console
+const MAX_OUTPUT_LINES: usize = 8;
+const FONT_SIZE: f32 = 12.;
+
+// everything the console understands, used for autocompletion
+const COMPLETIONS: &[&str] = &[
+    "help",
+    "clear",
+    "set ball.speed ",
+    "set ball.radius ",
+    "set paddle.width ",
+    "set paddle.height ",
+    "set rules.winning_score ",
+    "score left ",
+    "score right ",
+    "spawn ball",
+    "timescale ",
+    "reload rules",
+];
+
+const HELP: &str = "commands: set <ball.speed|ball.radius|paddle.width|paddle.height|\
+rules.winning_score> <value>, score <left|right> <points>, spawn ball, timescale <scale>, \
+reload rules, clear";
+
+// entered commands and their output, kept as a `Resource` so they survive closing the console
+#[derive(Default)]
+pub struct ConsoleLog {
+    history: Vec<String>,
+    output: Vec<String>,
+}
+
+impl ConsoleLog {
+    fn print(&mut self, line: String) {
+        self.output.push(line);
+        let excess = self.output.len().saturating_sub(MAX_OUTPUT_LINES);
+        self.output.drain(..excess);
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: Option<&str>, what: &str) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("missing {}", what))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid {}: {}", what, value))
+}
+
+// a size of the ball or the paddles, anything else would break the game mid-match
+fn parse_size(value: Option<&str>, what: &str, max: f32) -> Result<f32, String> {
+    let size: f32 = parse(value, what)?;
+    if !size.is_finite() || size <= 0.0 || size > max {
+        return Err(format!(
+            "the {} has to be positive and at most {}",
+            what, max
+        ));
+    }
+    Ok(size)
+}
+
+// resizes the sprites of every entity with `role` to match their new size
+fn resize_sprites(world: &World, role: SpriteRole, size: impl Fn(Entity) -> Option<[f32; 2]>) {
+    let (entities, theme, mut themed, mut transforms) = world.system_data::<(
+        Entities<'_>,
+        ReadExpect<'_, ThemeAssets>,
+        WriteStorage<'_, Themed>,
+        WriteStorage<'_, Transform>,
+    )>();
+    for (entity, themed, transform) in (&entities, &mut themed, &mut transforms).join() {
+        if themed.role != role {
+            continue;
+        }
+        if let Some(new_size) = size(entity) {
+            themed.size = Some(new_size);
+            transform.set_scale(theme.scale(role, new_size));
+        }
+    }
+}
+
+fn set(world: &World, property: &str, value: Option<&str>) -> Result<String, String> {
+    match property {
+        "ball.speed" => {
+            let speed: f32 = parse(value, "speed")?;
+            let mut balls = world.write_storage::<Ball>();
+            for ball in (&mut balls).join() {
+                let current = f32::hypot(ball.velocity[0], ball.velocity[1]);
+                if current > 0.0 {
+                    ball.velocity[0] *= speed / current;
+                    ball.velocity[1] *= speed / current;
+                }
+            }
+        }
+        "ball.radius" => {
+            // the ball has to fit between the walls
+            let radius = parse_size(value, "radius", ARENA_HEIGHT.min(ARENA_WIDTH) * 0.5)?;
+            for ball in (&mut world.write_storage::<Ball>()).join() {
+                ball.radius = radius;
+            }
+            let balls = world.read_storage::<Ball>();
+            resize_sprites(world, SpriteRole::Ball, |entity| {
+                balls
+                    .get(entity)
+                    .map(|ball| [ball.radius * 2.0, ball.radius * 2.0])
+            });
+        }
+        "paddle.width" | "paddle.height" => {
+            // paddles moving sideways stay inside their own half
+            let length = if property == "paddle.width" {
+                parse_size(value, "width", ARENA_WIDTH * 0.5)?
+            } else {
+                parse_size(value, "height", ARENA_HEIGHT)?
+            };
+            for paddle in (&mut world.write_storage::<Paddle>()).join() {
+                if property == "paddle.width" {
+                    paddle.width = length;
+                } else {
+                    paddle.height = length;
+                }
+            }
+            let paddles = world.read_storage::<Paddle>();
+            resize_sprites(world, SpriteRole::Paddle, |entity| {
+                paddles
+                    .get(entity)
+                    .map(|paddle| [paddle.width, paddle.height])
+            });
+        }
+        "rules.winning_score" => {
+            world.write_resource::<Rules>().winning_score = parse(value, "score")?;
+        }
+        _ => return Err(format!("unknown property: {}", property)),
+    }
+    Ok(format!("{} = {}", property, value.unwrap_or_default()))
+}
+
+// runs a single command against the `world`, returns what to print
+fn execute(world: &mut World, line: &str) -> Result<String, String> {
+    let mut words = line.split_whitespace();
+    let command = match words.next() {
+        Some(command) => command,
+        None => return Ok(String::new()),
+    };
+    match command {
+        "help" => Ok(HELP.to_string()),
+        "set" => {
+            let property = words.next().ok_or("missing property")?;
+            set(world, property, words.next())
+        }
+        "score" => {
+            let side = words.next().ok_or("missing side")?;
+            let points: i32 = parse(words.next(), "points")?;
+            let mut scores = world.write_resource::<ScoreBoard>();
+            match side {
+                "left" => scores.score_left = points,
+                "right" => scores.score_right = points,
+                _ => return Err(format!("unknown side: {}", side)),
+            }
+            Ok(format!("{} score = {}", side, points))
+        }
+        "spawn" => match words.next() {
+            Some("ball") => {
+                initialise_ball(world);
+                Ok("spawned a ball".to_string())
+            }
+            _ => Err("only balls can be spawned".to_string()),
+        },
+        "timescale" => {
+            let scale: f32 = parse(words.next(), "scale")?;
+            if scale <= 0.0 {
+                return Err("the time scale has to be positive".to_string());
+            }
+            world.write_resource::<GameSpeed>().0 = scale;
+            Ok(format!("time scale = {}", scale))
+        }
+        "reload" => match words.next() {
+            Some("rules") => {
+                // the file given on the command line, if any
+                let path = world
+                    .read_resource::<Cli>()
+                    .rules_path()
+                    .map_err(|e| e.to_string())?;
+                let rules = Rules::load(path).map_err(|e| e.to_string())?;
+                let message = format!("winning score = {}", rules.winning_score);
+                world.insert(rules);
+                Ok(message)
+            }
+            _ => Err("only the rules can be reloaded".to_string()),
+        },
+        _ => Err(format!("unknown command: {}", command)),
+    }
+}
+
+// longest prefix shared by all `words`
+fn common_prefix<'a>(words: &[&'a str]) -> &'a str {
+    let first = words.first().copied().unwrap_or_default();
+    let length = words.iter().fold(first.len(), |length, word| {
+        first
+            .bytes()
+            .zip(word.bytes())
+            .take(length)
+            .take_while(|(a, b)| a == b)
+            .count()
+    });
+    &first[..length]
+}
+
+// drop-down console pushed on top of a running match, which stays paused while it is open
+#[derive(Default)]
+pub struct Console {
+    input: String,
+    // position in the history while browsing it, `None` while typing a new command
+    history_index: Option<usize>,
+    background: Option<Entity>,
+    output_label: Option<Entity>,
+    input_label: Option<Entity>,
+}
+
+impl Console {
+    fn refresh(&self, world: &mut World) {
+        let output = world.read_resource::<ConsoleLog>().output.join("\n");
+        let mut texts = world.write_storage::<UiText>();
+        if let Some(text) = self.output_label.and_then(|label| texts.get_mut(label)) {
+            text.text = output;
+        }
+        if let Some(text) = self.input_label.and_then(|label| texts.get_mut(label)) {
+            text.text = format!("> {}_", self.input);
+        }
+    }
+
+    fn submit(&mut self, world: &mut World) {
+        let line = std::mem::take(&mut self.input);
+        self.history_index = None;
+        if line.trim().is_empty() {
+            return;
+        }
+        if line.trim() == "clear" {
+            world.write_resource::<ConsoleLog>().output.clear();
+        } else {
+            let result = execute(world, &line);
+            let mut log = world.write_resource::<ConsoleLog>();
+            log.print(format!("> {}", line));
+            match result {
+                Ok(message) if message.is_empty() => {}
+                Ok(message) => log.print(message),
+                Err(error) => log.print(format!("error: {}", error)),
+            }
+        }
+        world.write_resource::<ConsoleLog>().history.push(line);
+    }
+
+    // walks through the entered commands, `back` goes to older ones
+    fn browse_history(&mut self, world: &World, back: bool) {
+        let log = world.read_resource::<ConsoleLog>();
+        if log.history.is_empty() {
+            return;
+        }
+        let last = log.history.len() - 1;
+        self.history_index = match (self.history_index, back) {
+            (None, true) => Some(last),
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) if index < last => Some(index + 1),
+            (_, false) => None,
+        };
+        self.input = self
+            .history_index
+            .map(|index| log.history[index].clone())
+            .unwrap_or_default();
+    }
+
+    // completes the input as far as it is unambiguous, listing the options otherwise
+    fn autocomplete(&mut self, world: &World) {
+        let matches = COMPLETIONS
+            .iter()
+            .copied()
+            .filter(|completion| completion.starts_with(self.input.as_str()))
+            .collect::<Vec<_>>();
+        let prefix = common_prefix(&matches);
+        if prefix.len() > self.input.len() {
+            self.input = prefix.to_string();
+        } else if matches.len() > 1 {
+            world.write_resource::<ConsoleLog>().print(
+                matches
+                    .iter()
+                    .map(|m| m.trim())
+                    .collect::<Vec<_>>()
+                    .join("  "),
+            );
+        }
+    }
+
+    fn create_text(
+        world: &mut World,
+        id: &str,
+        y: f32,
+        height: f32,
+        anchor: Anchor,
+        line_mode: LineMode,
+    ) -> Entity {
+        let font = load_font(world);
+        let color = text_color(world);
+        let transform = UiTransform::new(
+            id.to_string(),
+            Anchor::TopMiddle,
+            Anchor::TopMiddle,
+            0.,
+            y,
+            11.,
+            480.,
+            height,
+        );
+        world
+            .create_entity()
+            .with(ScaledUi::new(&transform, FONT_SIZE))
+            .with(transform)
+            .with(UiText::new(
+                font,
+                String::new(),
+                color,
+                FONT_SIZE,
+                line_mode,
+                anchor,
+            ))
+            .build()
+    }
+}
+
+impl SimpleState for Console {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world
+            .entry::<ConsoleLog>()
+            .or_insert_with(ConsoleLog::default);
+
+        let transform = UiTransform::new(
+            "console_background".to_string(),
+            Anchor::TopMiddle,
+            Anchor::TopMiddle,
+            0.,
+            0.,
+            10.,
+            500.,
+            150.,
+        );
+        self.background = Some(
+            world
+                .create_entity()
+                .with(ScaledUi::new(&transform, 0.))
+                .with(transform)
+                .with(UiImage::SolidColor([0.0, 0.0, 0.0, 0.85]))
+                .build(),
+        );
+        self.output_label = Some(Self::create_text(
+            world,
+            "console_output",
+            -5.,
+            120.,
+            Anchor::BottomLeft,
+            LineMode::Wrap,
+        ));
+        self.input_label = Some(Self::create_text(
+            world,
+            "console_input",
+            -130.,
+            FONT_SIZE,
+            Anchor::MiddleLeft,
+            LineMode::Single,
+        ));
+        self.refresh(world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let entities = [self.background, self.output_label, self.input_label];
+        data.world
+            .delete_entities(&entities.iter().flatten().copied().collect::<Vec<_>>())
+            .expect("Failed to delete console");
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        let world = data.world;
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(event) {
+                return Trans::Quit;
+            }
+            if is_key_down(event, VirtualKeyCode::Grave)
+                || is_key_down(event, VirtualKeyCode::Escape)
+            {
+                return Trans::Pop;
+            }
+
+            if is_key_down(event, VirtualKeyCode::Return) {
+                self.submit(world);
+            } else if is_key_down(event, VirtualKeyCode::Back) {
+                self.input.pop();
+            } else if is_key_down(event, VirtualKeyCode::Up) {
+                self.browse_history(world, true);
+            } else if is_key_down(event, VirtualKeyCode::Down) {
+                self.browse_history(world, false);
+            } else if is_key_down(event, VirtualKeyCode::Tab) {
+                self.autocomplete(world);
+            } else if let Event::WindowEvent {
+                event: WindowEvent::ReceivedCharacter(c),
+                ..
+            } = event
+            {
+                // the key opening the console also arrives as a character
+                if !c.is_control() && *c != '`' {
+                    self.input.push(*c);
+                }
+            } else {
+                return Trans::None;
+            }
+            self.refresh(world);
+        }
+        Trans::None
+    }
+}
@@ -0,0 +1,207 @@
+use clap::ValueEnum;
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::pong::Side;
+
+// how well the computer plays
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize, ValueEnum)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    // fraction of the full paddle speed the computer moves at
+    pub fn speed(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.6,
+            Difficulty::Normal => 0.75,
+            Difficulty::Hard => 1.0,
+        }
+    }
+
+    // how far from the ball the computer may aim, in arena units, it misses when aiming further
+    // than half a paddle plus the ball's radius away
+    pub fn aim_error(self) -> f32 {
+        match self {
+            Difficulty::Easy => 14.0,
+            Difficulty::Normal => 12.0,
+            Difficulty::Hard => 10.5,
+        }
+    }
+}
+
+// what moves a paddle
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Controller {
+    // the paddle's axis in `config/bindings.ron`
+    Keyboard,
+    // the computer follows the ball
+    Ai(Difficulty),
+    // `PaddleInputs` are set by code driving the match, such as a training environment
+    External,
+    // an external program connected through `Bots`
+    Bot,
+}
+
+impl Controller {
+    // name of the computer player, if the controller is one
+    pub fn ai_name(self) -> Option<String> {
+        match self {
+            Controller::Keyboard | Controller::External | Controller::Bot => None,
+            Controller::Ai(difficulty) => Some(format!("CPU ({:?})", difficulty)),
+        }
+    }
+
+    // dashes and smashes belong to players and the code standing in for them, computer players
+    // and bots only move their paddle
+    pub fn uses_abilities(self) -> bool {
+        matches!(self, Controller::Keyboard | Controller::External)
+    }
+}
+
+// controllers of both paddles, as a `Resource`
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Controllers {
+    pub left: Controller,
+    pub right: Controller,
+}
+
+impl Controllers {
+    pub fn get(&self, side: Side) -> Controller {
+        match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+        }
+    }
+}
+
+impl Default for Controllers {
+    fn default() -> Self {
+        GameMode::default().controllers(Difficulty::Normal)
+    }
+}
+
+// who plays against whom
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize, ValueEnum)]
+pub enum GameMode {
+    // two players sharing the keyboard
+    #[default]
+    Versus,
+    // a player on the left against the computer
+    Single,
+    // the computer against itself
+    Watch,
+}
+
+impl GameMode {
+    pub fn controllers(self, difficulty: Difficulty) -> Controllers {
+        let ai = Controller::Ai(difficulty);
+        let (left, right) = match self {
+            GameMode::Versus => (Controller::Keyboard, Controller::Keyboard),
+            GameMode::Single => (Controller::Keyboard, ai),
+            GameMode::Watch => (ai, ai),
+        };
+        Controllers { left, right }
+    }
+}
+
+// axis value of each paddle for the current frame, between `-1.0` and `1.0`, written by
+// `PaddleInputSystem` from the controllers and applied by `PaddleSystem`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PaddleInputs {
+    pub left: f32,
+    pub right: f32,
+}
+
+impl PaddleInputs {
+    pub fn get(&self, side: Side) -> f32 {
+        match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+        }
+    }
+
+    pub fn set(&mut self, side: Side, value: f32) {
+        match side {
+            Side::Left => self.left = value,
+            Side::Right => self.right = value,
+        }
+    }
+}
+
+// sideways axis value of each paddle, towards the right is positive, only used when the rules
+// let paddles move horizontally
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HorizontalInputs {
+    pub left: f32,
+    pub right: f32,
+}
+
+impl HorizontalInputs {
+    pub fn get(&self, side: Side) -> f32 {
+        match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+        }
+    }
+
+    pub fn set(&mut self, side: Side, value: f32) {
+        match side {
+            Side::Left => self.left = value,
+            Side::Right => self.right = value,
+        }
+    }
+}
+
+// whether the ability buttons of a paddle are held this frame
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AbilityInput {
+    pub dash: bool,
+    pub smash: bool,
+}
+
+// ability buttons of both paddles, filled like `PaddleInputs` and used by `AbilitySystem`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AbilityInputs {
+    pub left: AbilityInput,
+    pub right: AbilityInput,
+}
+
+impl AbilityInputs {
+    pub fn get(&self, side: Side) -> AbilityInput {
+        match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+        }
+    }
+
+    pub fn set(&mut self, side: Side, value: AbilityInput) {
+        match side {
+            Side::Left => self.left = value,
+            Side::Right => self.right = value,
+        }
+    }
+}
+
+// source of every random gameplay decision as a `Resource`, seeded so matches can be repeated,
+// purely visual effects do not use it
+pub struct GameRng(pub StdRng);
+
+impl GameRng {
+    // a random seed is chosen when there is none
+    pub fn new(seed: Option<u64>) -> Self {
+        Self(match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        })
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
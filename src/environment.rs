@@ -0,0 +1,292 @@
+use amethyst::{
+    core::Transform,
+    ecs::{Join, ReadStorage},
+    prelude::*,
+};
+
+use crate::{
+    controls::{Controller, Controllers, PaddleInputs},
+    pong::{Ball, Paddle, ScoreBoard, Side, ARENA_HEIGHT, ARENA_WIDTH},
+    rules::Rules,
+    simulation::Simulation,
+};
+
+// brightness of the paddles and the ball in pixel observations, the background is `0`
+const PIXEL_ON: u8 = 255;
+
+// configuration of a `PongEnv`
+#[derive(Clone, Debug)]
+pub struct EnvConfig {
+    pub rules: Rules,
+    // number of simulation steps every `step` call advances, repeating the same actions
+    pub frame_skip: u32,
+    // width and height of the pixel observations, none are rendered if `None`
+    pub pixels: Option<(u32, u32)>,
+    // episodes end after this many `step` calls even without a winner
+    pub max_steps: Option<u64>,
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        Self {
+            rules: Rules::default(),
+            frame_skip: 4,
+            pixels: None,
+            max_steps: None,
+        }
+    }
+}
+
+// what the agents see after every step, in arena units with `(0, 0)` at the bottom left
+#[derive(Clone, Debug, PartialEq)]
+pub struct Observation {
+    pub ball_position: [f32; 2],
+    pub ball_velocity: [f32; 2],
+    // vertical position of the centre of each paddle
+    pub left_paddle: f32,
+    pub right_paddle: f32,
+    // greyscale image of the arena, row by row from the top, if enabled in `EnvConfig`
+    pub pixels: Option<Vec<u8>>,
+}
+
+impl Observation {
+    // the ball position and velocity followed by the paddle positions, for models taking vectors
+    pub fn to_vec(&self) -> Vec<f32> {
+        vec![
+            self.ball_position[0],
+            self.ball_position[1],
+            self.ball_velocity[0],
+            self.ball_velocity[1],
+            self.left_paddle,
+            self.right_paddle,
+        ]
+    }
+}
+
+// a Gym-style environment around a headless match, both paddles are controlled by the caller,
+// no window, GPU or audio device is needed
+pub struct PongEnv {
+    config: EnvConfig,
+    simulation: Simulation,
+    steps: u64,
+}
+
+impl PongEnv {
+    pub fn new(config: EnvConfig) -> Self {
+        let simulation = new_simulation(&config, None);
+        Self {
+            config,
+            simulation,
+            steps: 0,
+        }
+    }
+
+    // starts a new match, the same seed always plays out the same way for the same actions
+    pub fn reset(&mut self, seed: Option<u64>) -> Observation {
+        self.simulation = new_simulation(&self.config, seed);
+        self.steps = 0;
+        self.observe()
+    }
+
+    // moves the paddles by the given axis values between `-1.0` (down) and `1.0` (up) for
+    // `frame_skip` simulation steps, the reward is `1.0` when the left player scores and `-1.0`
+    // when the right one does, so it is the right player's reward negated
+    pub fn step(&mut self, action_left: f32, action_right: f32) -> (Observation, f32, bool) {
+        {
+            let mut inputs = self.simulation.world.write_resource::<PaddleInputs>();
+            inputs.left = action_left.clamp(-1.0, 1.0);
+            inputs.right = action_right.clamp(-1.0, 1.0);
+        }
+
+        let mut reward = 0.0;
+        for _ in 0..self.config.frame_skip.max(1) {
+            let before = self.simulation.scores();
+            self.simulation.step();
+            reward += reward_for_left(&before, &self.simulation.scores());
+            if self.simulation.winner().is_some() {
+                break;
+            }
+        }
+        self.steps += 1;
+
+        let done = self.simulation.winner().is_some()
+            || self.config.max_steps.is_some_and(|max| self.steps >= max);
+        (self.observe(), reward, done)
+    }
+
+    pub fn scores(&self) -> ScoreBoard {
+        self.simulation.scores()
+    }
+
+    fn observe(&self) -> Observation {
+        let world = &self.simulation.world;
+        let (paddles, balls, transforms) = world.system_data::<(
+            ReadStorage<'_, Paddle>,
+            ReadStorage<'_, Ball>,
+            ReadStorage<'_, Transform>,
+        )>();
+
+        let mut observation = Observation {
+            ball_position: [ARENA_WIDTH * 0.5, ARENA_HEIGHT * 0.5],
+            ball_velocity: [0.0, 0.0],
+            left_paddle: ARENA_HEIGHT * 0.5,
+            right_paddle: ARENA_HEIGHT * 0.5,
+            pixels: None,
+        };
+        if let Some((ball, transform)) = (&balls, &transforms).join().next() {
+            observation.ball_position = [transform.translation().x, transform.translation().y];
+            observation.ball_velocity = ball.velocity;
+        }
+        for (paddle, transform) in (&paddles, &transforms).join() {
+            match paddle.side {
+                Side::Left => observation.left_paddle = transform.translation().y,
+                Side::Right => observation.right_paddle = transform.translation().y,
+            }
+        }
+
+        if let Some((width, height)) = self.config.pixels {
+            let mut canvas = Canvas::new(width, height);
+            for (paddle, transform) in (&paddles, &transforms).join() {
+                let [x, y] = [transform.translation().x, transform.translation().y];
+                canvas.fill_rect(x, y, paddle.width * 0.5, paddle.height * 0.5);
+            }
+            for (ball, transform) in (&balls, &transforms).join() {
+                let [x, y] = [transform.translation().x, transform.translation().y];
+                canvas.fill_rect(x, y, ball.radius, ball.radius);
+            }
+            observation.pixels = Some(canvas.pixels);
+        }
+        observation
+    }
+}
+
+// `1.0` for every goal of the left player and `-1.0` for every goal of the right one
+fn reward_for_left(before: &ScoreBoard, after: &ScoreBoard) -> f32 {
+    (after.score_left - before.score_left) as f32 - (after.score_right - before.score_right) as f32
+}
+
+fn new_simulation(config: &EnvConfig, seed: Option<u64>) -> Simulation {
+    let controllers = Controllers {
+        left: Controller::External,
+        right: Controller::External,
+    };
+    Simulation::new(config.rules.clone(), controllers, seed)
+}
+
+// greyscale image the arena is drawn onto, row by row from the top
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; (width * height) as usize],
+        }
+    }
+
+    // fills the rectangle centred on `(x, y)` in arena units, clipped to the arena
+    fn fill_rect(&mut self, x: f32, y: f32, half_width: f32, half_height: f32) {
+        // nothing to draw on, or the object is completely outside, e.g. a ball past a goal
+        if self.pixels.is_empty()
+            || x + half_width <= 0.0
+            || x - half_width >= ARENA_WIDTH
+            || y + half_height <= 0.0
+            || y - half_height >= ARENA_HEIGHT
+        {
+            return;
+        }
+        let scale_x = self.width as f32 / ARENA_WIDTH;
+        let scale_y = self.height as f32 / ARENA_HEIGHT;
+        let column = |x: f32| ((x * scale_x).round().max(0.0) as u32).min(self.width);
+        // rows grow downwards while arena coordinates grow upwards
+        let row =
+            |y: f32| (((ARENA_HEIGHT - y) * scale_y).round().max(0.0) as u32).min(self.height);
+
+        // small objects cover at least one pixel, so they never disappear at low resolutions, the
+        // same at every edge of the arena
+        let left = column(x - half_width).min(self.width - 1);
+        let right = column(x + half_width).max(left + 1);
+        let top = row(y + half_height).min(self.height - 1);
+        let bottom = row(y - half_height).max(top + 1);
+        for row in top..bottom {
+            let start = (row * self.width + left) as usize;
+            let end = (row * self.width + right) as usize;
+            self.pixels[start..end].fill(PIXEL_ON);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scores(score_left: i32, score_right: i32) -> ScoreBoard {
+        ScoreBoard {
+            score_left,
+            score_right,
+        }
+    }
+
+    fn lit(canvas: &Canvas) -> Vec<(u32, u32)> {
+        (0..canvas.height)
+            .flat_map(|row| (0..canvas.width).map(move |column| (column, row)))
+            .filter(|(column, row)| canvas.pixels[(row * canvas.width + column) as usize] != 0)
+            .collect()
+    }
+
+    #[test]
+    fn goals_of_the_left_player_are_rewarded() {
+        assert_eq!(reward_for_left(&scores(0, 0), &scores(1, 0)), 1.0);
+        assert_eq!(reward_for_left(&scores(3, 2), &scores(3, 3)), -1.0);
+        assert_eq!(reward_for_left(&scores(3, 2), &scores(3, 2)), 0.0);
+    }
+
+    #[test]
+    fn rectangles_are_drawn_with_the_top_row_first() {
+        // one pixel per arena unit
+        let mut canvas = Canvas::new(ARENA_WIDTH as u32, ARENA_HEIGHT as u32);
+        canvas.fill_rect(1.0, ARENA_HEIGHT - 1.0, 1.0, 1.0);
+
+        assert_eq!(lit(&canvas), vec![(0, 0), (1, 0), (0, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn small_objects_cover_at_least_one_pixel() {
+        let mut canvas = Canvas::new(10, 10);
+        canvas.fill_rect(ARENA_WIDTH * 0.5, ARENA_HEIGHT * 0.5, 0.01, 0.01);
+
+        assert_eq!(lit(&canvas).len(), 1);
+    }
+
+    #[test]
+    fn rectangles_are_clipped_to_the_arena() {
+        let mut canvas = Canvas::new(10, 10);
+        canvas.fill_rect(0.0, 0.0, ARENA_WIDTH * 0.2, ARENA_HEIGHT * 0.2);
+
+        // the part inside the arena is the bottom left corner
+        assert_eq!(lit(&canvas), vec![(0, 8), (1, 8), (0, 9), (1, 9)]);
+    }
+
+    #[test]
+    fn objects_outside_the_arena_are_not_drawn() {
+        let mut canvas = Canvas::new(10, 10);
+        canvas.fill_rect(-3.0, ARENA_HEIGHT * 0.5, 2.0, 2.0);
+        canvas.fill_rect(ARENA_WIDTH + 3.0, ARENA_HEIGHT * 0.5, 2.0, 2.0);
+
+        assert!(lit(&canvas).is_empty());
+    }
+
+    #[test]
+    fn objects_barely_inside_are_drawn_at_either_edge() {
+        let mut canvas = Canvas::new(10, 10);
+        canvas.fill_rect(-1.6, ARENA_HEIGHT * 0.5 - 5.0, 2.0, 0.01);
+        canvas.fill_rect(ARENA_WIDTH + 1.6, ARENA_HEIGHT * 0.5 - 5.0, 2.0, 0.01);
+
+        assert_eq!(lit(&canvas), vec![(0, 5), (9, 5)]);
+    }
+}
@@ -0,0 +1,25 @@
+use crate::pong::Side;
+
+// gameplay events published by the gameplay `System`s through an `EventChannel<GameEvent>`,
+// effects such as particles subscribe to them instead of being called directly
+#[derive(Clone, Debug)]
+pub enum GameEvent {
+    // the ball bounced off the top or the bottom of the arena, `speed` is the ball's speed
+    WallBounce {
+        position: [f32; 2],
+        speed: f32,
+    },
+    // the ball bounced off the paddle on `side`, `speed` is the ball's speed after the hit,
+    // `offset` is where it hit the paddle, from `-1.0` at the bottom to `1.0` at the top edge
+    PaddleHit {
+        side: Side,
+        position: [f32; 2],
+        speed: f32,
+        offset: f32,
+    },
+    // the player on `scorer` side scored, `position` is where the ball left the arena
+    Goal {
+        scorer: Side,
+        position: [f32; 2],
+    },
+}
@@ -0,0 +1,149 @@
+use amethyst::{
+    ecs::Entity,
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::*,
+};
+
+use crate::{
+    leaderboard::Leaderboard,
+    pong::{Players, Pong, ScoreBoard, Side},
+    rating::{RatingChange, Ratings},
+    replay::Replay,
+    ui::{create_label, load_font},
+};
+
+// shown once a player reaches the winning score, displays the result and the rating changes
+pub struct GameOver {
+    score_left: i32,
+    score_right: i32,
+    // rating change of the winner and the loser, in this order
+    rating_changes: (RatingChange, RatingChange),
+    // UI entities that are removed when leaving the state
+    labels: Vec<Entity>,
+}
+
+impl GameOver {
+    // records the finished match in the persistent ratings and builds the screen displaying it
+    pub fn finish_match(world: &mut World, winner: Side) -> Self {
+        let (score_left, score_right) = {
+            let scores = world.read_resource::<ScoreBoard>();
+            (scores.score_left, scores.score_right)
+        };
+        let (winner_name, loser_name) = {
+            let players = world.read_resource::<Players>();
+            match winner {
+                Side::Left => (players.left.clone(), players.right.clone()),
+                Side::Right => (players.right.clone(), players.left.clone()),
+            }
+        };
+        let (winner_score, loser_score) = match winner {
+            Side::Left => (score_left, score_right),
+            Side::Right => (score_right, score_left),
+        };
+
+        let rating_changes = {
+            let mut ratings = world.write_resource::<Ratings>();
+            let changes =
+                ratings.record_match(&winner_name, &loser_name, winner_score, loser_score);
+            ratings.save();
+            changes
+        };
+
+        world.read_resource::<Replay>().save();
+
+        Self {
+            score_left,
+            score_right,
+            rating_changes,
+            labels: Vec::new(),
+        }
+    }
+
+    fn show(&mut self, world: &mut World) {
+        let font = load_font(world);
+        let (winner, loser) = &self.rating_changes;
+
+        let lines = [
+            ("game_over_title", 40., format!("{} wins!", winner.name)),
+            (
+                "game_over_score",
+                30.,
+                format!("{} - {}", self.score_left, self.score_right),
+            ),
+            ("game_over_winner_rating", 20., format_change(winner)),
+            ("game_over_loser_rating", 20., format_change(loser)),
+            (
+                "game_over_help",
+                15.,
+                "SPACE: rematch  L: leaderboard  ESC: menu".to_string(),
+            ),
+        ];
+
+        let mut y = -60.;
+        for (id, size, text) in lines.iter() {
+            let label = create_label(world, font.clone(), id, y, *size, text.clone());
+            self.labels.push(label);
+            y -= size + 30.;
+        }
+    }
+
+    fn hide(&mut self, world: &mut World) {
+        world
+            .delete_entities(&self.labels)
+            .expect("Failed to delete game over labels");
+        self.labels.clear();
+    }
+}
+
+// e.g. "Player 1: 1500 -> 1516 (+16)"
+fn format_change(change: &RatingChange) -> String {
+    format!(
+        "{}: {:.0} -> {:.0} ({:+.0})",
+        change.name,
+        change.old_rating,
+        change.new_rating,
+        change.delta()
+    )
+}
+
+impl SimpleState for GameOver {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.show(data.world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.hide(data.world);
+    }
+
+    // the leaderboard is pushed on top of this state, so the labels are hidden meanwhile
+    fn on_pause(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.hide(data.world);
+    }
+
+    fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.show(data.world);
+    }
+
+    fn handle_event(
+        &mut self,
+        _data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(event) {
+                return Trans::Quit;
+            }
+            // the match was pushed on top of the main menu
+            if is_key_down(event, VirtualKeyCode::Escape) {
+                return Trans::Pop;
+            }
+            if is_key_down(event, VirtualKeyCode::Space) {
+                return Trans::Switch(Box::new(Pong::default()));
+            }
+            if is_key_down(event, VirtualKeyCode::L) {
+                return Trans::Push(Box::new(Leaderboard::default()));
+            }
+        }
+        Trans::None
+    }
+}
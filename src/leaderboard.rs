@@ -0,0 +1,88 @@
+use amethyst::{
+    ecs::Entity,
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::*,
+};
+
+use crate::{
+    rating::Ratings,
+    ui::{create_label, load_font},
+};
+
+// only this many profiles fit on the screen
+const MAX_ENTRIES: usize = 10;
+
+// lists the player profiles sorted by their rating, pushed on top of another state
+#[derive(Default)]
+pub struct Leaderboard {
+    labels: Vec<Entity>,
+}
+
+impl SimpleState for Leaderboard {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        let font = load_font(world);
+
+        let mut lines = vec![(
+            "leaderboard_title".to_string(),
+            30.,
+            "Leaderboard".to_string(),
+        )];
+        {
+            let ratings = world.read_resource::<Ratings>();
+            let entries = ratings.leaderboard();
+            if entries.is_empty() {
+                lines.push((
+                    "leaderboard_empty".to_string(),
+                    15.,
+                    "No matches played yet".to_string(),
+                ));
+            }
+            for (rank, (name, player)) in entries.into_iter().take(MAX_ENTRIES).enumerate() {
+                lines.push((
+                    format!("leaderboard_{}", rank),
+                    15.,
+                    format!(
+                        "{:>2}. {}  {:.0}  ({}W/{}L)",
+                        rank + 1,
+                        name,
+                        player.rating,
+                        player.wins,
+                        player.losses
+                    ),
+                ));
+            }
+        }
+        lines.push(("leaderboard_help".to_string(), 15., "ESC: back".to_string()));
+
+        let mut y = -40.;
+        for (id, size, text) in lines {
+            self.labels
+                .push(create_label(world, font.clone(), &id, y, size, text));
+            y -= size + 15.;
+        }
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        data.world
+            .delete_entities(&self.labels)
+            .expect("Failed to delete leaderboard labels");
+        self.labels.clear();
+    }
+
+    fn handle_event(
+        &mut self,
+        _data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(event) {
+                return Trans::Quit;
+            }
+            if is_key_down(event, VirtualKeyCode::Escape) || is_key_down(event, VirtualKeyCode::L) {
+                return Trans::Pop;
+            }
+        }
+        Trans::None
+    }
+}
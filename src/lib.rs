@@ -0,0 +1,35 @@
+// the game as a library, used by the `amethyst-pong-tutorial` binary and by code embedding the
+// game or driving matches on its own, such as training environments
+pub mod abilities;
+pub mod audio;
+pub mod bot;
+pub mod bundle;
+pub mod cli;
+pub mod console;
+pub mod controls;
+pub mod environment;
+pub mod events;
+pub mod game_over;
+pub mod leaderboard;
+pub mod menu;
+pub mod options;
+pub mod pause;
+pub mod persist;
+pub mod pong;
+pub mod rating;
+pub mod replay;
+pub mod rules;
+pub mod savegame;
+pub mod settings;
+pub mod simulation;
+pub mod sounds;
+pub mod systems;
+pub mod telemetry;
+pub mod theme;
+pub mod tournament;
+pub mod ui;
+
+pub use crate::{
+    bundle::PongBundle,
+    pong::{Ball, Paddle, ScoreBoard},
+};
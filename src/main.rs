@@ -1,29 +1,153 @@
 use amethyst::{
-    audio::{AudioBundle, DjSystemDesc},
-    core::transform::TransformBundle,
+    audio::AudioBundle,
+    config::Config,
+    core::{transform::TransformBundle, SystemExt},
     input::{InputBundle, StringBindings},
     prelude::*,
     renderer::{
-        plugins::{RenderFlat2D, RenderToWindow},
+        plugins::{RenderDebugLines, RenderFlat2D, RenderToWindow},
         types::DefaultBackend,
         RenderingBundle,
     },
     ui::{RenderUi, UiBundle},
-    utils::application_root_dir,
+    utils::{application_root_dir, fps_counter::FpsCounterBundle},
+    window::DisplayConfig,
+    LoggerConfig,
 };
+use amethyst_pong_tutorial::{
+    audio::MusicConfig,
+    cli::Cli,
+    controls::{Controllers, GameRng, PaddleInputs},
+    menu::MainMenu,
+    pong::{Players, RunState, Side},
+    rating::Ratings,
+    replay::{Replay, ReplayViewer},
+    rules::Rules,
+    settings::Settings,
+    simulation::{Simulation, TICK},
+    sounds::SoundConfig,
+    systems,
+    theme::{Theme, DEFAULT_THEME},
+    tournament, PongBundle,
+};
+use clap::Parser;
+
+// headless matches give up after this many steps, an hour of play
+const HEADLESS_MAX_TICKS: u64 = 60 * 60 * 60;
+
+// first state of the game, goes to the replay given on the command line or the main menu
+struct Launch {
+    replay: Option<Replay>,
+}
+
+impl SimpleState for Launch {
+    fn update(&mut self, _data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        match self.replay.take() {
+            Some(replay) => Trans::Switch(Box::new(ReplayViewer::new(replay))),
+            None => Trans::Switch(Box::new(MainMenu::default())),
+        }
+    }
+}
 
-mod audio;
-mod pong;
-mod systems;
+// plays a single match without a window and prints its result
+fn run_headless(cli: &Cli, rules: Rules, controllers: Controllers) -> amethyst::Result<()> {
+    let mut simulation =
+        Simulation::new(rules, controllers, cli.seed).with_telemetry(cli.telemetry()?);
+    simulation.world.insert(cli.bots()?);
+    let winner = simulation.run(HEADLESS_MAX_TICKS);
+    let scores = simulation.scores();
+    let ticks = simulation.ticks();
+    match winner {
+        Some(winner) => println!(
+            "{:?} wins {} - {} after {:.1} seconds",
+            winner,
+            scores.score_left,
+            scores.score_right,
+            ticks as f32 * TICK
+        ),
+        None => println!(
+            "No winner after {:.1} seconds, {} - {}",
+            ticks as f32 * TICK,
+            scores.score_left,
+            scores.score_right
+        ),
+    }
+    Ok(())
+}
 
-use crate::{audio::Music, pong::Pong};
+// plays the matches of a tournament without a window and prints the report
+fn run_tournament(
+    cli: &Cli,
+    rules: Rules,
+    controllers: Controllers,
+    matches: u32,
+) -> amethyst::Result<()> {
+    let name = |side: Side| {
+        cli.player_name(side)
+            .unwrap_or_else(|| format!("{:?}", side))
+    };
+    let report = tournament::run_tournament(
+        [name(Side::Left), name(Side::Right)],
+        &rules,
+        controllers,
+        cli.bots()?,
+        matches,
+        cli.seed.unwrap_or(0),
+        HEADLESS_MAX_TICKS,
+    );
+    print!("{}", report.table());
+    if let Some(path) = &cli.report {
+        report.save(path)?;
+    }
+    Ok(())
+}
 
 fn main() -> amethyst::Result<()> {
-    amethyst::start_logger(Default::default());
+    let cli = Cli::parse();
+    amethyst::start_logger(LoggerConfig {
+        level_filter: cli.log_level,
+        ..Default::default()
+    });
 
     let app_root = application_root_dir()?;
     let display_config_path = app_root.join("config").join("display.ron");
 
+    let rules = Rules::load(cli.rules_path()?)?;
+    if cli.headless {
+        return run_headless(&cli, rules, cli.headless_controllers()?);
+    }
+    if let Some(matches) = cli.tournament {
+        return run_tournament(&cli, rules, cli.headless_controllers()?, matches);
+    }
+    let controllers = cli.controllers();
+    let replay = match &cli.replay {
+        Some(path) => Some(Replay::load(path)?),
+        None => None,
+    };
+
+    let mut display_config = DisplayConfig::load(display_config_path)?;
+    if let Some(dimensions) = cli.window_size {
+        display_config.dimensions = Some(dimensions);
+    }
+
+    let settings = Settings::load();
+    // the window is cleared with the colour of the theme active at startup, afterwards the
+    // arena's background sprite follows theme switches
+    let clear_color = Theme::load_named(&settings.display.theme)
+        .or_else(|_| Theme::load_named(DEFAULT_THEME))?
+        .clear_color;
+    let mut players = Players::load(app_root.join("config").join("players.ron"))?;
+    // computer players and bots have profiles of their own
+    if let Some(name) = cli.player_name(Side::Left) {
+        players.left = name;
+    }
+    if let Some(name) = cli.player_name(Side::Right) {
+        players.right = name;
+    }
+    let music = MusicConfig::load(app_root.join("config").join("music.ron"))?;
+    let sounds = SoundConfig::load(app_root.join("config").join("sounds.ron"))?;
+    sounds.check_files(&app_root.join("assets"))?;
+
     let binding_path = app_root.join("config").join("bindings.ron");
     let input_bundle =
         InputBundle::<StringBindings>::new().with_bindings_from_file(binding_path)?;
@@ -37,35 +161,83 @@ fn main() -> amethyst::Result<()> {
         .with_bundle(
             RenderingBundle::<DefaultBackend>::new()
                 // provides scaffolding for creating a window and drawing to it
-                .with_plugin(
-                    RenderToWindow::from_config_path(display_config_path)?
-                        .with_clear([0.0, 0.0, 0.0, 1.0]),
-                )
+                .with_plugin(RenderToWindow::from_config(display_config).with_clear(clear_color))
                 // plugin used to render entities with a `SpriteRender` component
                 .with_plugin(RenderFlat2D::default())
+                // draws the collision bounds of the debug overlay
+                .with_plugin(RenderDebugLines::default())
                 .with_plugin(RenderUi::default()),
         )?
         // needs to come after `InputBundle`
         .with_bundle(UiBundle::<StringBindings>::new())?
         .with_bundle(AudioBundle::default())?
+        .with_bundle(FpsCounterBundle)?
+        .with(
+            systems::MusicSystem::default(),
+            "music_system",
+            &["input_system"],
+        )
+        // gameplay `System`s, paused outside of a running match
+        .with_bundle(PongBundle::new().with_dep(&["input_system"]))?
+        // pauses itself outside of a running match
         .with_system_desc(
-            DjSystemDesc::new(|music: &mut Music| music.music.next()),
-            "dj_system",
-            &[],
+            systems::ParticleSystemDesc,
+            "particle_system",
+            &["collision_system", "winner_system"],
         )
         // provide an instance of the `System`, a string name and a list of dependencies
-        .with(systems::PaddleSystem, "paddle_system", &["input_system"])
-        .with(systems::MoveBallsSystem, "ball_system", &[])
         .with(
-            systems::BounceSystem,
-            "collision_system",
-            &["paddle_system", "ball_system"],
+            systems::ReplayRecorderSystem::default().pausable(RunState::Running),
+            "replay_recorder_system",
+            &["collision_system", "winner_system"],
+        )
+        .with_system_desc(
+            systems::HudSystemDesc,
+            "hud_system",
+            &["collision_system", "winner_system"],
+        )
+        .with_system_desc(
+            systems::CameraEffectsSystemDesc,
+            "camera_effects_system",
+            &["collision_system", "winner_system"],
+        )
+        .with(systems::ViewportSystem, "viewport_system", &[])
+        .with(
+            systems::DebugOverlaySystem::default(),
+            "debug_overlay_system",
+            &["input_system", "collision_system"],
         )
-        .with(systems::WinnerSystem, "winner_system", &["ball_system"]);
+        .with(
+            systems::WindowModeSystem::default(),
+            "window_mode_system",
+            &["input_system"],
+        )
+        .with(
+            systems::ThemeSystem::default(),
+            "theme_system",
+            &["input_system"],
+        );
 
     let assets_dir = app_root.join("assets");
 
-    let mut game = Application::new(assets_dir, Pong::default(), game_data)?;
+    let mut game = Application::build(assets_dir, Launch { replay })?
+        .with_resource(rules)
+        .with_resource(controllers)
+        .with_resource(cli.mode)
+        .with_resource(PaddleInputs::default())
+        .with_resource(GameRng::new(cli.seed))
+        .with_resource(cli.bots()?)
+        .with_resource(players)
+        .with_resource(music)
+        .with_resource(sounds)
+        // ratings persist across sessions in the user directory
+        .with_resource(Ratings::load())
+        .with_resource(settings)
+        .with_resource(cli.telemetry()?)
+        .with_resource(cli)
+        // states handle closing the window themselves, so a running match can be saved first
+        .ignore_window_close(true)
+        .build(game_data)?;
     game.run();
     Ok(())
 }
@@ -0,0 +1,107 @@
+use amethyst::{
+    ecs::Entity,
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::*,
+};
+
+use crate::{
+    audio::{initialise_audio, play_playlist, PlaylistKind},
+    leaderboard::Leaderboard,
+    options::OptionsMenu,
+    pong::Pong,
+    savegame::SavedMatch,
+    sounds::{SoundEvent, SoundParams, SoundPlayer},
+    theme::initialise_theme,
+    ui::{create_label, load_font},
+};
+
+// first state of the game, matches and the leaderboard are pushed on top of it
+#[derive(Default)]
+pub struct MainMenu {
+    labels: Vec<Entity>,
+}
+
+impl MainMenu {
+    fn show(&mut self, world: &mut World) {
+        let font = load_font(world);
+
+        let mut lines = vec![
+            ("menu_title", 50., "Pong!"),
+            ("menu_new", 15., "N: new match"),
+        ];
+        if SavedMatch::exists() {
+            lines.push(("menu_resume", 15., "R: resume saved match"));
+        }
+        lines.push(("menu_leaderboard", 15., "L: leaderboard"));
+        lines.push(("menu_options", 15., "O: options"));
+        lines.push(("menu_quit", 15., "ESC: quit"));
+
+        let mut y = -80.;
+        for (id, size, text) in lines {
+            let label = create_label(world, font.clone(), id, y, size, text.to_string());
+            self.labels.push(label);
+            y -= size + 20.;
+        }
+    }
+
+    fn hide(&mut self, world: &mut World) {
+        world
+            .delete_entities(&self.labels)
+            .expect("Failed to delete menu labels");
+        self.labels.clear();
+    }
+}
+
+impl SimpleState for MainMenu {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        initialise_theme(data.world);
+        initialise_audio(data.world);
+        play_playlist(data.world, PlaylistKind::Menu);
+        self.show(data.world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.hide(data.world);
+    }
+
+    // every menu entry pushes another state
+    fn on_pause(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        data.world
+            .system_data::<SoundPlayer>()
+            .play(SoundEvent::MenuSelect, SoundParams::default());
+        self.hide(data.world);
+    }
+
+    // the saved match might have changed while another state was active
+    fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        play_playlist(data.world, PlaylistKind::Menu);
+        self.show(data.world);
+    }
+
+    fn handle_event(
+        &mut self,
+        _data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(event) || is_key_down(event, VirtualKeyCode::Escape) {
+                return Trans::Quit;
+            }
+            if is_key_down(event, VirtualKeyCode::N) {
+                return Trans::Push(Box::new(Pong::default()));
+            }
+            if is_key_down(event, VirtualKeyCode::R) {
+                if let Some(saved) = SavedMatch::take() {
+                    return Trans::Push(Box::new(Pong::resume(saved)));
+                }
+            }
+            if is_key_down(event, VirtualKeyCode::L) {
+                return Trans::Push(Box::new(Leaderboard::default()));
+            }
+            if is_key_down(event, VirtualKeyCode::O) {
+                return Trans::Push(Box::new(OptionsMenu::default()));
+            }
+        }
+        Trans::None
+    }
+}
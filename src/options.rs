@@ -0,0 +1,212 @@
+use amethyst::{
+    ecs::{Entity, WriteStorage},
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::*,
+    ui::UiText,
+};
+
+use crate::{
+    settings::Settings,
+    sounds::{SoundEvent, SoundParams, SoundPlayer},
+    theme::Theme,
+    ui::{create_label, load_font},
+};
+
+// volumes change by this much per key press
+const VOLUME_STEP: f32 = 0.05;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum OptionItem {
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    Mute,
+    Shuffle,
+    WindowMode,
+    Theme,
+    ScreenEffects,
+    Particles,
+}
+
+const ITEMS: [OptionItem; 9] = [
+    OptionItem::MasterVolume,
+    OptionItem::MusicVolume,
+    OptionItem::SfxVolume,
+    OptionItem::Mute,
+    OptionItem::Shuffle,
+    OptionItem::WindowMode,
+    OptionItem::Theme,
+    OptionItem::ScreenEffects,
+    OptionItem::Particles,
+];
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+impl OptionItem {
+    fn text(self, settings: &Settings) -> String {
+        let percent = |volume: f32| format!("{:.0}%", volume * 100.0);
+        match self {
+            OptionItem::MasterVolume => {
+                format!("Master volume: {}", percent(settings.audio.master_volume))
+            }
+            OptionItem::MusicVolume => {
+                format!("Music volume: {}", percent(settings.audio.music_volume))
+            }
+            OptionItem::SfxVolume => {
+                format!("Effects volume: {}", percent(settings.audio.sfx_volume))
+            }
+            OptionItem::Mute => format!("Mute: {}", on_off(settings.audio.muted)),
+            OptionItem::Shuffle => format!("Shuffle music: {}", on_off(settings.audio.shuffle)),
+            OptionItem::WindowMode => format!("Window: {:?}", settings.display.mode),
+            OptionItem::Theme => format!("Theme: {}", settings.display.theme),
+            OptionItem::ScreenEffects => {
+                format!("Screen shake: {}", on_off(settings.camera_effects.enabled))
+            }
+            OptionItem::Particles => format!("Particles: {}", on_off(settings.particles.enabled)),
+        }
+    }
+
+    // changes the setting one step in `direction`, which is either `1` or `-1`
+    fn adjust(self, settings: &mut Settings, direction: f32) {
+        let step = |volume: &mut f32| {
+            // rounding keeps repeated steps from drifting away from round percentages
+            *volume = ((*volume + VOLUME_STEP * direction) / VOLUME_STEP).round() * VOLUME_STEP;
+            *volume = volume.clamp(0.0, 1.0);
+        };
+        match self {
+            OptionItem::MasterVolume => step(&mut settings.audio.master_volume),
+            OptionItem::MusicVolume => step(&mut settings.audio.music_volume),
+            OptionItem::SfxVolume => step(&mut settings.audio.sfx_volume),
+            OptionItem::Mute => settings.audio.muted = !settings.audio.muted,
+            OptionItem::Shuffle => settings.audio.shuffle = !settings.audio.shuffle,
+            OptionItem::WindowMode => settings.display.mode = settings.display.mode.next(),
+            OptionItem::Theme => {
+                let themes = Theme::available();
+                if !themes.is_empty() {
+                    let current = themes
+                        .iter()
+                        .position(|name| *name == settings.display.theme)
+                        .unwrap_or(0) as isize;
+                    let next = (current + direction as isize).rem_euclid(themes.len() as isize);
+                    settings.display.theme = themes[next as usize].clone();
+                }
+            }
+            OptionItem::ScreenEffects => {
+                settings.camera_effects.enabled = !settings.camera_effects.enabled
+            }
+            OptionItem::Particles => settings.particles.enabled = !settings.particles.enabled,
+        }
+    }
+}
+
+// edits the `Settings` resource, changes take effect immediately and are saved when leaving
+#[derive(Default)]
+pub struct OptionsMenu {
+    selected: usize,
+    // one label per entry of `ITEMS`
+    items: Vec<Entity>,
+    // title and help texts
+    labels: Vec<Entity>,
+}
+
+impl OptionsMenu {
+    // rewrites every item's text, marking the selected one
+    fn refresh(&self, world: &mut World) {
+        let settings = (*world.read_resource::<Settings>()).clone();
+        world.exec(|mut texts: WriteStorage<'_, UiText>| {
+            for (index, (item, entity)) in ITEMS.iter().zip(self.items.iter()).enumerate() {
+                if let Some(text) = texts.get_mut(*entity) {
+                    let marker = if index == self.selected { "> " } else { "" };
+                    text.text = format!("{}{}", marker, item.text(&settings));
+                }
+            }
+        });
+    }
+}
+
+impl SimpleState for OptionsMenu {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        let font = load_font(world);
+
+        self.labels.push(create_label(
+            world,
+            font.clone(),
+            "options_title",
+            -40.,
+            30.,
+            "Options".to_string(),
+        ));
+
+        let mut y = -100.;
+        for index in 0..ITEMS.len() {
+            let id = format!("options_item_{}", index);
+            let label = create_label(world, font.clone(), &id, y, 15., String::new());
+            self.items.push(label);
+            y -= 30.;
+        }
+
+        self.labels.push(create_label(
+            world,
+            font,
+            "options_help",
+            y - 20.,
+            12.,
+            "UP/DOWN: select  LEFT/RIGHT: change  ESC: back".to_string(),
+        ));
+
+        self.refresh(world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        data.world.read_resource::<Settings>().save();
+        data.world
+            .delete_entities(&self.items)
+            .expect("Failed to delete option labels");
+        data.world
+            .delete_entities(&self.labels)
+            .expect("Failed to delete option labels");
+        self.items.clear();
+        self.labels.clear();
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(event) {
+                return Trans::Quit;
+            }
+            if is_key_down(event, VirtualKeyCode::Escape) || is_key_down(event, VirtualKeyCode::O) {
+                return Trans::Pop;
+            }
+
+            if is_key_down(event, VirtualKeyCode::Up) {
+                self.selected = (self.selected + ITEMS.len() - 1) % ITEMS.len();
+            } else if is_key_down(event, VirtualKeyCode::Down) {
+                self.selected = (self.selected + 1) % ITEMS.len();
+            } else if is_key_down(event, VirtualKeyCode::Left) {
+                ITEMS[self.selected].adjust(&mut data.world.write_resource(), -1.0);
+            } else if is_key_down(event, VirtualKeyCode::Right)
+                || is_key_down(event, VirtualKeyCode::Return)
+            {
+                ITEMS[self.selected].adjust(&mut data.world.write_resource(), 1.0);
+            } else {
+                return Trans::None;
+            }
+            data.world
+                .system_data::<SoundPlayer>()
+                .play(SoundEvent::MenuSelect, SoundParams::default());
+            self.refresh(data.world);
+        }
+        Trans::None
+    }
+}
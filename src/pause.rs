@@ -0,0 +1,108 @@
+use amethyst::{
+    ecs::Entity,
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::*,
+};
+
+use crate::{
+    options::OptionsMenu,
+    savegame::SavedMatch,
+    ui::{create_label, load_font},
+};
+
+// pushed on top of `Pong`, gameplay `System`s do not run while this state is active
+pub struct Pause {
+    // serve timer of the paused match, needed when saving it
+    ball_spawn_timer: Option<f32>,
+    labels: Vec<Entity>,
+}
+
+impl Pause {
+    pub fn new(ball_spawn_timer: Option<f32>) -> Self {
+        Self {
+            ball_spawn_timer,
+            labels: Vec::new(),
+        }
+    }
+
+    fn save(&self, world: &World) {
+        SavedMatch::capture(world, self.ball_spawn_timer).save();
+    }
+
+    fn show(&mut self, world: &mut World) {
+        let font = load_font(world);
+
+        let lines = [
+            ("pause_title", 40., "Paused"),
+            ("pause_resume", 15., "ESC: resume"),
+            ("pause_options", 15., "O: options"),
+            ("pause_save", 15., "S: save and quit to menu"),
+            ("pause_quit", 15., "Q: quit to menu"),
+        ];
+
+        let mut y = -100.;
+        for (id, size, text) in lines.iter() {
+            let label = create_label(world, font.clone(), id, y, *size, text.to_string());
+            self.labels.push(label);
+            y -= size + 20.;
+        }
+    }
+
+    fn hide(&mut self, world: &mut World) {
+        world
+            .delete_entities(&self.labels)
+            .expect("Failed to delete pause labels");
+        self.labels.clear();
+    }
+}
+
+// leaves both the pause menu and the match, returning to the main menu below them
+fn back_to_menu() -> SimpleTrans {
+    Trans::Sequence(vec![Trans::Pop, Trans::Pop])
+}
+
+impl SimpleState for Pause {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.show(data.world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.hide(data.world);
+    }
+
+    // the options menu is pushed on top of this state
+    fn on_pause(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.hide(data.world);
+    }
+
+    fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.show(data.world);
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(event) {
+                self.save(data.world);
+                return Trans::Quit;
+            }
+            if is_key_down(event, VirtualKeyCode::Escape) || is_key_down(event, VirtualKeyCode::P) {
+                return Trans::Pop;
+            }
+            if is_key_down(event, VirtualKeyCode::S) {
+                self.save(data.world);
+                return back_to_menu();
+            }
+            if is_key_down(event, VirtualKeyCode::Q) {
+                return back_to_menu();
+            }
+            if is_key_down(event, VirtualKeyCode::O) {
+                return Trans::Push(Box::new(OptionsMenu::default()));
+            }
+        }
+        Trans::None
+    }
+}
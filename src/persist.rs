@@ -0,0 +1,57 @@
+use amethyst::{config::Config, utils::application_root_dir};
+use log::warn;
+use std::{fs, path::PathBuf};
+
+// everything the game writes at runtime lives in this directory, next to `config` and `assets`
+const USER_DIR: &str = "user";
+
+// returns the path of a file inside the user directory, creating the directory if needed
+pub fn user_file(name: &str) -> Option<PathBuf> {
+    let dir = application_root_dir().ok()?.join(USER_DIR);
+    if let Err(e) = fs::create_dir_all(&dir) {
+        warn!("Could not create user directory {:?}: {}", dir, e);
+        return None;
+    }
+    Some(dir.join(name))
+}
+
+// loads a `.ron` file from the user directory, returns `None` if it does not exist
+// or cannot be parsed
+pub fn try_load_user_file<T: Config>(name: &str) -> Option<T> {
+    let path = user_file(name)?;
+    if !path.exists() {
+        return None;
+    }
+    T::load(&path)
+        .map_err(|e| warn!("Could not load {:?}: {}", path, e))
+        .ok()
+}
+
+// loads a `.ron` file from the user directory, falling back to the default value
+// if the file does not exist yet or cannot be parsed
+pub fn load_user_file<T: Config + Default>(name: &str) -> T {
+    try_load_user_file(name).unwrap_or_default()
+}
+
+// writes a value as a `.ron` file into the user directory
+pub fn save_user_file<T: Config>(name: &str, value: &T) {
+    if let Some(path) = user_file(name) {
+        if let Err(e) = value.write(&path) {
+            warn!("Could not save {:?}: {}", path, e);
+        }
+    }
+}
+
+pub fn user_file_exists(name: &str) -> bool {
+    user_file(name).is_some_and(|path| path.exists())
+}
+
+pub fn remove_user_file(name: &str) {
+    if let Some(path) = user_file(name) {
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                warn!("Could not remove {:?}: {}", path, e);
+            }
+        }
+    }
+}
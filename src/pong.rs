@@ -1,14 +1,31 @@
 use amethyst::core::Time;
 use amethyst::{
-    assets::{AssetStorage, Handle, Loader},
     core::transform::Transform,
-    ecs::{Component, DenseVecStorage, Entity},
+    ecs::{Component, DenseVecStorage, Entity, EntityBuilder},
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
     prelude::*,
-    renderer::{Camera, ImageFormat, SpriteRender, SpriteSheet, SpriteSheetFormat, Texture},
-    ui::{Anchor, LineMode, TtfFormat, UiText, UiTransform},
+    renderer::Camera,
+    ui::UiCreator,
 };
 
-use crate::audio::initialise_audio;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    abilities::Abilities,
+    audio::{initialise_audio, play_playlist, PlaylistKind},
+    console::Console,
+    controls::GameRng,
+    game_over::GameOver,
+    pause::Pause,
+    replay::Replay,
+    rules::Rules,
+    savegame::SavedMatch,
+    sounds::{SoundEvent, SoundParams, SoundPlayer},
+    systems::CameraShake,
+    telemetry::Telemetry,
+    theme::{initialise_theme, themed_sprite, SpriteRole, ThemeAssets},
+};
 
 pub const ARENA_WIDTH: f32 = 100.0;
 pub const ARENA_HEIGHT: f32 = 100.0;
@@ -19,38 +36,118 @@ pub const PADDLE_HEIGHT: f32 = 16.0;
 pub const BALL_VELOCITY_X: f32 = 75.0;
 pub const BALL_VELOCITY_Y: f32 = 50.0;
 pub const BALL_RADIUS: f32 = 2.0;
+// fastest the ball is sent up or down by spin, twice that of the serve
+pub const MAX_SPIN_VELOCITY_Y: f32 = BALL_VELOCITY_Y * 2.0;
+
+const HUD_PREFAB: &str = "ui/hud.ron";
 
 #[derive(Default)]
 pub struct Pong {
     // counts down to `Some(0)` then gets replaced with `None`
     ball_spawn_timer: Option<f32>,
-    // only populated in `on_start`
-    sprite_sheet_handle: Option<Handle<SpriteSheet>>,
+    // if present, the match continues from this snapshot instead of starting from zero
+    saved_match: Option<SavedMatch>,
+    // rules of the session, put back when a resumed match played by its saved rules ends
+    session_rules: Option<Rules>,
+}
+
+impl Pong {
+    // continues a match that was saved earlier
+    pub fn resume(saved_match: SavedMatch) -> Self {
+        Self {
+            saved_match: Some(saved_match),
+            ..Default::default()
+        }
+    }
+
+    fn save(&self, world: &World) {
+        SavedMatch::capture(world, self.ball_spawn_timer).save();
+    }
 }
 
 impl SimpleState for Pong {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         let world = data.world;
 
-        // wait one second before spawning the ball
-        self.ball_spawn_timer.replace(1.0);
-
-        // `Clone`able reference to the `SpriteSheet`
-        self.sprite_sheet_handle.replace(load_sprite_sheet(world));
+        // sprites and fonts come from the active theme
+        initialise_theme(world);
 
         // components not used in any `System`s need to be manually registered in the `world`
         // as `Paddle` is used in `PaddleSystem`, this is no longer necessary
         // world.register::<Paddle>();
 
-        // `clone` is required as the function consumes this handle
-        initialise_paddles(world, self.sprite_sheet_handle.clone().unwrap());
+        if let Some(saved) = self.saved_match.take() {
+            self.session_rules = Some((*world.read_resource::<Rules>()).clone());
+            self.ball_spawn_timer = restore_match(world, saved);
+        } else {
+            // wait one second before spawning the ball
+            self.ball_spawn_timer.replace(1.0);
+
+            initialise_paddles(world);
+
+            // every match starts from zero
+            world.insert(ScoreBoard::default());
+            world.insert(MatchStats::default());
+        }
+        world.insert(GameSpeed::default());
+        world.insert(Abilities::default());
+        // resumed matches are recorded from where they continue
+        let players = (*world.read_resource::<Players>()).clone();
+        world.insert(Replay::new(players));
+        world.write_resource::<Telemetry>().start_match();
         initialise_camera(world);
+        initialise_letterbox(world);
 
-        initialise_scoreboard(world);
+        initialise_hud(world);
         initialise_audio(world);
+        play_playlist(world, PlaylistKind::Match);
+
+        *world.write_resource() = RunState::Running;
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        *data.world.write_resource() = RunState::Paused;
+        if let Some(rules) = self.session_rules.take() {
+            data.world.insert(rules);
+        }
+        // remove the arena, the ball and the score texts, the next match creates them again
+        data.world.delete_all();
+    }
+
+    fn on_pause(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        *data.world.write_resource() = RunState::Paused;
+    }
+
+    fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        *data.world.write_resource() = RunState::Running;
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(event) {
+                // closing the window keeps the match, so it can be resumed from the main menu
+                self.save(data.world);
+                return Trans::Quit;
+            }
+            if is_key_down(event, VirtualKeyCode::Escape) || is_key_down(event, VirtualKeyCode::P) {
+                return Trans::Push(Box::new(Pause::new(self.ball_spawn_timer)));
+            }
+            if is_key_down(event, VirtualKeyCode::Grave) {
+                return Trans::Push(Box::new(Console::default()));
+            }
+        }
+        Trans::None
     }
 
     fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if let Some(winner) = match_winner(data.world) {
+            return Trans::Switch(Box::new(GameOver::finish_match(data.world, winner)));
+        }
+
         if let Some(mut timer) = self.ball_spawn_timer.take() {
             // if timer has not expired, subtract elapsed time (`take` consumes the `Option`, leaving `None`)
             {
@@ -60,7 +157,7 @@ impl SimpleState for Pong {
 
             if timer <= 0.0 {
                 // if the timer expired, spawn the ball in
-                initialise_ball(data.world, self.sprite_sheet_handle.clone().unwrap());
+                initialise_ball(data.world);
             } else {
                 // if the timer has not expired, put its current value back in the state
                 self.ball_spawn_timer.replace(timer);
@@ -70,24 +167,72 @@ impl SimpleState for Pong {
     }
 }
 
-#[derive(Eq, PartialEq)]
+// base speed of the game as a `Resource`, `1.0` is normal, changed from the developer console
+#[derive(Clone, Copy, Debug)]
+pub struct GameSpeed(pub f32);
+
+impl Default for GameSpeed {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+// gameplay `System`s only run while this `Resource` is `Running`
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RunState {
+    Running,
+    // nothing is in play until a match is started
+    #[default]
+    Paused,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Side {
     Left,
     Right,
 }
 
+impl Side {
+    pub fn opposite(self) -> Self {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Paddle {
     pub side: Side,
     pub width: f32,
     pub height: f32,
+    // vertical speed in arena units per second
+    #[serde(default)]
+    pub velocity: f32,
+    // horizontal speed, only moving when the rules let paddles leave their goal line
+    #[serde(default)]
+    pub velocity_x: f32,
 }
 
 impl Paddle {
-    fn new(side: Side) -> Self {
+    pub fn new(side: Side) -> Self {
         Self {
             side,
             width: PADDLE_WIDTH,
             height: PADDLE_HEIGHT,
+            velocity: 0.0,
+            velocity_x: 0.0,
+        }
+    }
+
+    // the horizontal range the paddle's centre can move in, its own half of the arena, or only
+    // its goal line if it is too wide for that, the first bound is never the larger one
+    pub fn zone(&self) -> (f32, f32) {
+        let goal_line = self.width * 0.5;
+        let half_way = ((ARENA_WIDTH - self.width) * 0.5).max(goal_line);
+        match self.side {
+            Side::Left => (goal_line, half_way),
+            Side::Right => (ARENA_WIDTH - half_way, ARENA_WIDTH - goal_line),
         }
     }
 }
@@ -97,36 +242,7 @@ impl Component for Paddle {
     type Storage = DenseVecStorage<Self>;
 }
 
-// the sprites inside the sheet are ordered based on their definitions inside the spritesheet file
-fn load_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
-    // asset loader `Resource`, responsible for loading in various types of assets
-    let loader = world.read_resource::<Loader>();
-
-    // `Clone`able reference to the texture
-    let texture_handle = {
-        // Amethyst manages `Texture`s internally, so it must be loaded into an `AssetStorage`
-        let texture_storage = world.read_resource::<AssetStorage<Texture>>();
-        loader.load(
-            "texture/pong_spritesheet.png",
-            ImageFormat::default(),
-            (),
-            &texture_storage,
-        )
-    };
-
-    // Amethyst manages `SpriteSheet`s internally, so it must be loaded into an `AssetStorage`
-    let sprite_sheet_store = world.read_resource::<AssetStorage<SpriteSheet>>();
-    loader.load(
-        // load the `.ron` file which specifies the sprites
-        "texture/pong_spritesheet.ron",
-        // extract sprites from the loaded texture
-        SpriteSheetFormat(texture_handle),
-        (),
-        &sprite_sheet_store,
-    )
-}
-
-fn initialise_camera(world: &mut World) {
+pub fn initialise_camera(world: &mut World) {
     // setup camera in a way that our screen covers whole arena and (0, 0) is in the bottom left
     let mut transform = Transform::default();
     transform.set_translation_xyz(ARENA_WIDTH * 0.5, ARENA_HEIGHT * 0.5, 1.0);
@@ -136,11 +252,81 @@ fn initialise_camera(world: &mut World) {
         // create orthographic camera useful for 2D rendering, `z` is 1.0 as sprites are at `0.0`
         .with(Camera::standard_2d(ARENA_WIDTH, ARENA_HEIGHT))
         .with(transform)
+        // the camera returns here after being shaken
+        .with(CameraShake {
+            origin: [ARENA_WIDTH * 0.5, ARENA_HEIGHT * 0.5],
+        })
         .build();
 }
 
+// fills the arena with the theme's background colour and covers everything outside of it with
+// the theme's letterbox colour, visible when the window's aspect ratio differs from the arena's
+pub fn initialise_letterbox(world: &mut World) {
+    // large enough to cover the bars of any reasonable window shape
+    let size = ARENA_WIDTH.max(ARENA_HEIGHT) * 10.0;
+
+    let rects = [
+        // the background, behind everything in the arena
+        (
+            SpriteRole::Background,
+            ARENA_WIDTH * 0.5,
+            ARENA_HEIGHT * 0.5,
+            -0.5,
+            ARENA_WIDTH,
+            ARENA_HEIGHT,
+        ),
+        // the bars, in front of everything in the arena, so particles leaving it are hidden
+        (
+            SpriteRole::Letterbox,
+            -size * 0.5,
+            ARENA_HEIGHT * 0.5,
+            0.5,
+            size,
+            size,
+        ),
+        (
+            SpriteRole::Letterbox,
+            ARENA_WIDTH + size * 0.5,
+            ARENA_HEIGHT * 0.5,
+            0.5,
+            size,
+            size,
+        ),
+        (
+            SpriteRole::Letterbox,
+            ARENA_WIDTH * 0.5,
+            -size * 0.5,
+            0.5,
+            size,
+            size,
+        ),
+        (
+            SpriteRole::Letterbox,
+            ARENA_WIDTH * 0.5,
+            ARENA_HEIGHT + size * 0.5,
+            0.5,
+            size,
+            size,
+        ),
+    ];
+    for (role, x, y, z, width, height) in rects.iter() {
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(*x, *y, *z);
+        let (sprite, tint, transform, themed) =
+            themed_sprite(world, *role, Some([*width, *height]), transform);
+
+        world
+            .create_entity()
+            .with(sprite)
+            .with(tint)
+            .with(transform)
+            .with(themed)
+            .build();
+    }
+}
+
 // initialises one paddle on the left and one paddle on the right
-fn initialise_paddles(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>) {
+pub fn initialise_paddles(world: &mut World) {
     let mut left_transform = Transform::default();
     let mut right_transform = Transform::default();
 
@@ -151,28 +337,27 @@ fn initialise_paddles(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet
     left_transform.set_translation_xyz(PADDLE_WIDTH * 0.5, y, 0.0);
     right_transform.set_translation_xyz(ARENA_WIDTH - PADDLE_WIDTH * 0.5, y, 0.0);
 
-    // the paddle sprite is the first one inside the sheet
-    // one is enough as both paddles look exactly the same
-    let sprite_render = SpriteRender::new(sprite_sheet_handle, 0);
-
-    world
-        .create_entity()
-        .with(sprite_render.clone())
-        .with(Paddle::new(Side::Left))
-        .with(left_transform)
-        .build();
-
-    world
-        .create_entity()
-        .with(sprite_render)
-        .with(Paddle::new(Side::Right))
-        .with(right_transform)
-        .build();
+    for (side, transform) in [(Side::Left, left_transform), (Side::Right, right_transform)] {
+        create_paddle(world, Paddle::new(side), transform);
+    }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Ball {
     pub velocity: [f32; 2],
     pub radius: f32,
+    // given by the paddle that hit the ball last, curves its path when the rules allow it
+    #[serde(default)]
+    pub spin: f32,
+}
+
+impl Ball {
+    // speeds the ball up or down because of its spin, without spin alone taking it past
+    // `MAX_SPIN_VELOCITY_Y`, faster balls, e.g. smashed ones, are not slowed down
+    pub fn add_spin_velocity(&mut self, velocity_y: f32) {
+        let limit = self.velocity[1].abs().max(MAX_SPIN_VELOCITY_Y);
+        self.velocity[1] = (self.velocity[1] + velocity_y).clamp(-limit, limit);
+    }
 }
 
 impl Component for Ball {
@@ -180,91 +365,141 @@ impl Component for Ball {
 }
 
 // initialises one ball in the middle of the screen
-fn initialise_ball(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>) {
+pub fn initialise_ball(world: &mut World) {
     let mut transform = Transform::default();
     transform.set_translation_xyz(ARENA_WIDTH * 0.5, ARENA_HEIGHT * 0.5, 0.0);
 
-    // the ball is the second sprite in the sheet
-    let sprite_render = SpriteRender::new(sprite_sheet_handle, 1);
+    // the ball flies up or down at random
+    let direction_y = if world.write_resource::<GameRng>().0.gen_bool(0.5) {
+        1.0
+    } else {
+        -1.0
+    };
+    let ball = Ball {
+        radius: BALL_RADIUS,
+        velocity: [BALL_VELOCITY_X, BALL_VELOCITY_Y * direction_y],
+        spin: 0.0,
+    };
+    world.system_data::<SoundPlayer>().play(
+        SoundEvent::Serve,
+        SoundParams::for_ball(ARENA_WIDTH * 0.5, ball.velocity),
+    );
+    create_ball(world, ball, transform);
+}
+
+// the paddle's sprite is scaled to its size, whatever the theme's sprite looks like,
+// headless matches without a theme get no sprite at all
+pub fn create_paddle(world: &mut World, paddle: Paddle, transform: Transform) -> Entity {
+    let size = [paddle.width, paddle.height];
+    create_visible(world, SpriteRole::Paddle, size, transform)
+        .with(paddle)
+        .build()
+}
 
-    world
-        .create_entity()
-        .with(sprite_render)
-        .with(Ball {
-            radius: BALL_RADIUS,
-            velocity: [BALL_VELOCITY_X, BALL_VELOCITY_Y],
-        })
-        .with(transform)
-        .build();
+pub fn create_ball(world: &mut World, ball: Ball, transform: Transform) -> Entity {
+    let size = [ball.radius * 2.0, ball.radius * 2.0];
+    create_visible(world, SpriteRole::Ball, size, transform)
+        .with(ball)
+        .build()
+}
+
+// starts an entity drawn with the theme's sprite for `role`, if a theme is loaded
+fn create_visible(
+    world: &mut World,
+    role: SpriteRole,
+    size: [f32; 2],
+    transform: Transform,
+) -> EntityBuilder<'_> {
+    if world.has_value::<ThemeAssets>() {
+        let (sprite, tint, transform, themed) = themed_sprite(world, role, Some(size), transform);
+        world
+            .create_entity()
+            .with(sprite)
+            .with(tint)
+            .with(themed)
+            .with(transform)
+    } else {
+        world.create_entity().with(transform)
+    }
+}
+
+// recreates the paddles, balls and scores of a saved match, returns its serve timer
+fn restore_match(world: &mut World, saved: SavedMatch) -> Option<f32> {
+    for (paddle, transform) in saved.paddles {
+        create_paddle(world, paddle, transform);
+    }
+
+    for (ball, transform) in saved.balls {
+        create_ball(world, ball, transform);
+    }
+
+    world.insert(saved.scores);
+    world.insert(saved.stats);
+    world.insert(saved.rules);
+
+    saved.ball_spawn_timer
 }
 
 // contains score data
-#[derive(Default)] // important!
+#[derive(Clone, Debug, Default, Serialize, Deserialize)] // `Default` is important!
 pub struct ScoreBoard {
     pub score_left: i32,
     pub score_right: i32,
 }
 
-// contains UI components that display the score
-pub struct ScoreText {
-    pub p1_score: Entity,
-    pub p2_score: Entity,
+// statistics of the current match shown by the HUD, as a `Resource`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchStats {
+    // paddle hits since the last goal
+    pub rally: u32,
+    // seconds the match has been running, pauses excluded
+    pub elapsed: f32,
+    // player who served the ball in play, or serves the next one
+    pub server: Side,
 }
 
-fn initialise_scoreboard(world: &mut World) {
-    let font = world.read_resource::<Loader>().load(
-        "font/square.ttf",
-        TtfFormat,
-        (),
-        &world.read_resource(),
-    );
-    let p1_transform = UiTransform::new(
-        "P1".to_string(),
-        Anchor::TopMiddle,
-        Anchor::TopMiddle,
-        -50.,
-        -50.,
-        1.,
-        200.,
-        50.,
-    );
-    let p2_transform = UiTransform::new(
-        "P2".to_string(),
-        Anchor::TopMiddle,
-        Anchor::TopMiddle,
-        50.,
-        -50.,
-        1.,
-        200.,
-        50.,
-    );
+impl Default for MatchStats {
+    fn default() -> Self {
+        Self {
+            rally: 0,
+            elapsed: 0.0,
+            // the first ball flies to the right
+            server: Side::Left,
+        }
+    }
+}
 
-    let p1_score = world
-        .create_entity()
-        .with(p1_transform)
-        .with(UiText::new(
-            font.clone(),
-            "0".to_string(),
-            [1., 1., 1., 1.],
-            50.,
-            LineMode::Single,
-            Anchor::Middle,
-        ))
-        .build();
+// returns the side that reached the winning score, if any
+pub fn match_winner(world: &World) -> Option<Side> {
+    let scores = world.read_resource::<ScoreBoard>();
+    let rules = world.read_resource::<Rules>();
+    if scores.score_left >= rules.winning_score {
+        Some(Side::Left)
+    } else if scores.score_right >= rules.winning_score {
+        Some(Side::Right)
+    } else {
+        None
+    }
+}
 
-    let p2_score = world
-        .create_entity()
-        .with(p2_transform)
-        .with(UiText::new(
-            font,
-            "0".to_string(),
-            [1., 1., 1., 1.],
-            50.,
-            LineMode::Single,
-            Anchor::Middle,
-        ))
-        .build();
+// names of the player profiles controlling the paddles, read from `config/players.ron`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Players {
+    pub left: String,
+    pub right: String,
+}
+
+impl Default for Players {
+    fn default() -> Self {
+        Self {
+            left: "Player 1".to_string(),
+            right: "Player 2".to_string(),
+        }
+    }
+}
 
-    // add this as a `Resource` to the `world`, so it can be accessed by other functions/systems
-    world.insert(ScoreText { p1_score, p2_score });
+// lays out the scores, names and match statistics shown by `HudSystem`
+pub fn initialise_hud(world: &mut World) {
+    world.exec(|mut creator: UiCreator<'_>| creator.create(HUD_PREFAB, ()));
 }
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::persist::{load_user_file, save_user_file};
+
+const RATINGS_FILE: &str = "ratings.ron";
+
+// every new profile starts from this rating
+pub const DEFAULT_RATING: f32 = 1500.0;
+// maximum amount of points a single match can move a rating by
+const K_FACTOR: f32 = 32.0;
+
+// rating and match statistics of a single player profile
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlayerRating {
+    pub rating: f32,
+    pub wins: u32,
+    pub losses: u32,
+}
+
+impl Default for PlayerRating {
+    fn default() -> Self {
+        Self {
+            rating: DEFAULT_RATING,
+            wins: 0,
+            losses: 0,
+        }
+    }
+}
+
+// result of a finished match, kept as history next to the ratings
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MatchRecord {
+    pub winner: String,
+    pub loser: String,
+    pub winner_score: i32,
+    pub loser_score: i32,
+    pub rating_delta: f32,
+}
+
+// how a single player's rating moved after a match, used for display
+#[derive(Clone, Debug)]
+pub struct RatingChange {
+    pub name: String,
+    pub old_rating: f32,
+    pub new_rating: f32,
+}
+
+impl RatingChange {
+    pub fn delta(&self) -> f32 {
+        self.new_rating - self.old_rating
+    }
+}
+
+// persistent Elo ratings of all player profiles, stored as a `Resource`
+#[derive(Default, Serialize, Deserialize)]
+pub struct Ratings {
+    pub players: BTreeMap<String, PlayerRating>,
+    pub matches: Vec<MatchRecord>,
+}
+
+impl Ratings {
+    pub fn load() -> Self {
+        load_user_file(RATINGS_FILE)
+    }
+
+    pub fn save(&self) {
+        save_user_file(RATINGS_FILE, self);
+    }
+
+    pub fn rating(&self, name: &str) -> f32 {
+        self.players
+            .get(name)
+            .map_or(DEFAULT_RATING, |player| player.rating)
+    }
+
+    // updates both players' ratings according to the Elo formula and records the match,
+    // returns the rating changes of the winner and the loser, in this order
+    pub fn record_match(
+        &mut self,
+        winner: &str,
+        loser: &str,
+        winner_score: i32,
+        loser_score: i32,
+    ) -> (RatingChange, RatingChange) {
+        let winner_rating = self.rating(winner);
+        let loser_rating = self.rating(loser);
+
+        // the winner gains exactly as much as the loser loses
+        let delta = K_FACTOR * (1.0 - expected_score(winner_rating, loser_rating));
+
+        {
+            let player = self.players.entry(winner.to_string()).or_default();
+            player.rating = winner_rating + delta;
+            player.wins += 1;
+        }
+        {
+            let player = self.players.entry(loser.to_string()).or_default();
+            player.rating = loser_rating - delta;
+            player.losses += 1;
+        }
+
+        self.matches.push(MatchRecord {
+            winner: winner.to_string(),
+            loser: loser.to_string(),
+            winner_score,
+            loser_score,
+            rating_delta: delta,
+        });
+
+        (
+            RatingChange {
+                name: winner.to_string(),
+                old_rating: winner_rating,
+                new_rating: winner_rating + delta,
+            },
+            RatingChange {
+                name: loser.to_string(),
+                old_rating: loser_rating,
+                new_rating: loser_rating - delta,
+            },
+        )
+    }
+
+    // all profiles, highest rating first
+    pub fn leaderboard(&self) -> Vec<(&String, &PlayerRating)> {
+        let mut players = self.players.iter().collect::<Vec<_>>();
+        players.sort_by(|(_, a), (_, b)| b.rating.total_cmp(&a.rating));
+        players
+    }
+}
+
+// probability of a player with `rating` beating a player with `opponent_rating`
+fn expected_score(rating: f32, opponent_rating: f32) -> f32 {
+    1.0 / (1.0 + 10.0_f32.powf((opponent_rating - rating) / 400.0))
+}
@@ -0,0 +1,224 @@
+use amethyst::{
+    core::{Time, Transform},
+    ecs::{Entity, WriteStorage},
+    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    prelude::*,
+};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audio::initialise_audio,
+    persist::{save_user_file, user_file},
+    pong::{
+        create_ball, create_paddle, initialise_camera, initialise_hud, initialise_letterbox, Ball,
+        MatchStats, Paddle, Players, ScoreBoard,
+    },
+    theme::initialise_theme,
+};
+
+// the last finished match is always written here, inside the user directory
+pub const REPLAY_FILE: &str = "last_replay.ron";
+// seconds between two recorded frames, positions in between are interpolated on playback
+pub const REPLAY_INTERVAL: f32 = 1.0 / 30.0;
+
+// state of the match at a single point in time
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    // seconds since the recording started
+    pub time: f32,
+    pub paddles: Vec<(Paddle, [f32; 2])>,
+    pub balls: Vec<(Ball, [f32; 2])>,
+    pub scores: ScoreBoard,
+    pub stats: MatchStats,
+}
+
+// recording of a match as a `Resource`, filled by `ReplayRecorderSystem` while a match runs
+#[derive(Default, Serialize, Deserialize)]
+pub struct Replay {
+    pub players: Players,
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    pub fn new(players: Players) -> Self {
+        Self {
+            players,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn save(&self) {
+        save_user_file(REPLAY_FILE, self);
+        if let Some(path) = user_file(REPLAY_FILE) {
+            info!("Replay saved to {:?}", path);
+        }
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.frames.last().map_or(0.0, |frame| frame.time)
+    }
+
+    // the recorded frames right before and after `time`, and how far between them `time` is
+    fn frames_at(&self, time: f32) -> Option<(&ReplayFrame, &ReplayFrame, f32)> {
+        let next = self.frames.iter().position(|frame| frame.time > time);
+        match next {
+            Some(0) => self.frames.first().map(|frame| (frame, frame, 0.0)),
+            Some(index) => {
+                let (previous, next) = (&self.frames[index - 1], &self.frames[index]);
+                let blend = (time - previous.time) / (next.time - previous.time);
+                Some((previous, next, blend))
+            }
+            None => self.frames.last().map(|frame| (frame, frame, 0.0)),
+        }
+    }
+}
+
+// plays back a recorded match, SPACE restarts it and ESC quits
+pub struct ReplayViewer {
+    replay: Replay,
+    time: f32,
+    paddles: Vec<Entity>,
+    balls: Vec<Entity>,
+}
+
+impl ReplayViewer {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            time: 0.0,
+            paddles: Vec::new(),
+            balls: Vec::new(),
+        }
+    }
+
+    // moves the paddles and balls to where they were at `self.time`
+    fn show_frame(&mut self, world: &mut World) {
+        let (previous, next, blend) = match self.replay.frames_at(self.time) {
+            Some(frames) => frames,
+            None => return,
+        };
+        // positions are only interpolated while the same entities are in play
+        let lerp = |list: &[[f32; 2]], other: &[[f32; 2]], index: usize| {
+            let [x, y] = list[index];
+            match other.get(index) {
+                Some([next_x, next_y]) if list.len() == other.len() => {
+                    [x + (next_x - x) * blend, y + (next_y - y) * blend]
+                }
+                _ => [x, y],
+            }
+        };
+
+        let paddles = previous
+            .paddles
+            .iter()
+            .map(|(paddle, _)| paddle.clone())
+            .collect::<Vec<_>>();
+        let paddle_positions = positions(&previous.paddles);
+        let next_paddle_positions = positions(&next.paddles);
+        sync_entities(world, &mut self.paddles, paddles.len(), |world, index| {
+            create_paddle(world, paddles[index].clone(), Transform::default())
+        });
+
+        let balls = previous
+            .balls
+            .iter()
+            .map(|(ball, _)| ball.clone())
+            .collect::<Vec<_>>();
+        let ball_positions = positions(&previous.balls);
+        let next_ball_positions = positions(&next.balls);
+        sync_entities(world, &mut self.balls, balls.len(), |world, index| {
+            create_ball(world, balls[index].clone(), Transform::default())
+        });
+
+        world.exec(|mut transforms: WriteStorage<'_, Transform>| {
+            let moves = self
+                .paddles
+                .iter()
+                .enumerate()
+                .map(|(index, entity)| {
+                    (
+                        entity,
+                        lerp(&paddle_positions, &next_paddle_positions, index),
+                    )
+                })
+                .chain(self.balls.iter().enumerate().map(|(index, entity)| {
+                    (entity, lerp(&ball_positions, &next_ball_positions, index))
+                }));
+            for (entity, [x, y]) in moves {
+                if let Some(transform) = transforms.get_mut(*entity) {
+                    transform.set_translation_x(x);
+                    transform.set_translation_y(y);
+                }
+            }
+        });
+
+        world.insert(previous.scores.clone());
+        world.insert(MatchStats {
+            elapsed: self.time,
+            ..previous.stats.clone()
+        });
+    }
+}
+
+fn positions<T>(list: &[(T, [f32; 2])]) -> Vec<[f32; 2]> {
+    list.iter().map(|(_, position)| *position).collect()
+}
+
+// recreates `entities` if their number differs from `count`
+fn sync_entities(
+    world: &mut World,
+    entities: &mut Vec<Entity>,
+    count: usize,
+    mut create: impl FnMut(&mut World, usize) -> Entity,
+) {
+    if entities.len() == count {
+        return;
+    }
+    world
+        .delete_entities(entities)
+        .expect("Failed to delete replay entities");
+    *entities = (0..count).map(|index| create(world, index)).collect();
+}
+
+impl SimpleState for ReplayViewer {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        initialise_theme(world);
+        initialise_audio(world);
+        initialise_camera(world);
+        initialise_letterbox(world);
+        initialise_hud(world);
+
+        world.insert(self.replay.players.clone());
+        world.insert(ScoreBoard::default());
+        world.insert(MatchStats::default());
+
+        // gameplay `System`s stay paused, the recording moves everything
+        self.show_frame(world);
+    }
+
+    fn handle_event(
+        &mut self,
+        _data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(event) || is_key_down(event, VirtualKeyCode::Escape) {
+                return Trans::Quit;
+            }
+            if is_key_down(event, VirtualKeyCode::Space) {
+                self.time = 0.0;
+            }
+        }
+        Trans::None
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        let delta = data.world.read_resource::<Time>().delta_seconds();
+        self.time = (self.time + delta).min(self.replay.duration());
+        self.show_frame(data.world);
+        Trans::None
+    }
+}
@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+
+use crate::controls::{Controller, GameMode};
+
+// how the paddles move and how they affect the ball
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaddlePhysics {
+    // speeding up at full input, in arena units per second squared
+    pub acceleration: f32,
+    // share of the paddle's speed lost per second, the top speed is `acceleration / friction`
+    pub friction: f32,
+    // share of the paddle's vertical speed given to the ball when hitting it
+    pub spin: f32,
+    // how strongly the ball's path curves in the direction of its spin, `0.0` keeps it straight
+    pub curve: f32,
+    // paddles also move sideways, each within its own half of the arena
+    pub horizontal: bool,
+}
+
+impl Default for PaddlePhysics {
+    fn default() -> Self {
+        Self {
+            // top speed of 72 units per second, reached in about a tenth of a second
+            acceleration: 864.0,
+            friction: 12.0,
+            spin: 0.2,
+            curve: 0.0,
+            horizontal: false,
+        }
+    }
+}
+
+// the dash and the charged smash of the paddles, times in seconds
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AbilityRules {
+    // game modes the abilities can be used in
+    pub modes: Vec<GameMode>,
+    // speed the paddle is pushed to by a dash, in arena units per second
+    pub dash_speed: f32,
+    pub dash_cooldown: f32,
+    // holding the smash button this long charges it fully
+    pub smash_charge_time: f32,
+    // after releasing the button, the ball has to be hit within this time to be smashed
+    pub smash_window: f32,
+    // speed of a fully charged smash relative to a normal hit
+    pub smash_boost: f32,
+    // starts once the smash hit the ball or its window ran out
+    pub smash_cooldown: f32,
+}
+
+impl AbilityRules {
+    // whether the paddle of `controller` has abilities in a match of `mode`
+    pub fn enabled(&self, mode: GameMode, controller: Controller) -> bool {
+        controller.uses_abilities() && self.modes.contains(&mode)
+    }
+}
+
+impl Default for AbilityRules {
+    fn default() -> Self {
+        Self {
+            modes: vec![GameMode::Versus, GameMode::Single],
+            dash_speed: 240.0,
+            dash_cooldown: 1.5,
+            smash_charge_time: 0.5,
+            smash_window: 0.25,
+            smash_boost: 1.6,
+            smash_cooldown: 3.0,
+        }
+    }
+}
+
+// gameplay settings read from `config/rules.ron`, available as a `Resource`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    // the first player to reach this score wins the match
+    pub winning_score: i32,
+    pub paddle: PaddlePhysics,
+    pub abilities: AbilityRules,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            winning_score: 11,
+            paddle: PaddlePhysics::default(),
+            abilities: AbilityRules::default(),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use amethyst::{
+    core::Transform,
+    ecs::{Join, Read, ReadExpect, ReadStorage},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    persist::{remove_user_file, save_user_file, try_load_user_file, user_file_exists},
+    pong::{Ball, MatchStats, Paddle, ScoreBoard},
+    rules::Rules,
+};
+
+const SAVE_FILE: &str = "savegame.ron";
+
+// snapshot of an in-progress match, written to the user directory
+#[derive(Serialize, Deserialize)]
+pub struct SavedMatch {
+    pub scores: ScoreBoard,
+    // saves from before the HUD start with fresh statistics
+    #[serde(default)]
+    pub stats: MatchStats,
+    pub paddles: Vec<(Paddle, Transform)>,
+    pub balls: Vec<(Ball, Transform)>,
+    // time left until the ball is served, `None` if it is already in play
+    pub ball_spawn_timer: Option<f32>,
+    pub rules: Rules,
+}
+
+impl SavedMatch {
+    // collects the state of the current match from the `world`
+    pub fn capture(world: &World, ball_spawn_timer: Option<f32>) -> Self {
+        let (paddles, balls, transforms, scores, stats, rules) = world.system_data::<(
+            ReadStorage<'_, Paddle>,
+            ReadStorage<'_, Ball>,
+            ReadStorage<'_, Transform>,
+            Read<'_, ScoreBoard>,
+            Read<'_, MatchStats>,
+            ReadExpect<'_, Rules>,
+        )>();
+
+        Self {
+            scores: scores.clone(),
+            stats: stats.clone(),
+            paddles: (&paddles, &transforms)
+                .join()
+                .map(|(paddle, transform)| (paddle.clone(), transform.clone()))
+                .collect(),
+            balls: (&balls, &transforms)
+                .join()
+                .map(|(ball, transform)| (ball.clone(), transform.clone()))
+                .collect(),
+            ball_spawn_timer,
+            rules: rules.clone(),
+        }
+    }
+
+    pub fn save(&self) {
+        save_user_file(SAVE_FILE, self);
+    }
+
+    pub fn exists() -> bool {
+        user_file_exists(SAVE_FILE)
+    }
+
+    // loads the saved match and removes it from disk, so it can only be resumed once
+    pub fn take() -> Option<Self> {
+        let saved = try_load_user_file(SAVE_FILE);
+        remove_user_file(SAVE_FILE);
+        saved
+    }
+}
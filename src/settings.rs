@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    persist::{load_user_file, save_user_file, user_file_exists},
+    theme::DEFAULT_THEME,
+};
+
+const SETTINGS_FILE: &str = "settings.ron";
+
+// user preferences stored in the user directory, available as a `Resource`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub particles: ParticleSettings,
+    pub camera_effects: CameraEffectSettings,
+    pub display: DisplaySettings,
+    pub audio: AudioSettings,
+}
+
+impl Settings {
+    // loads the settings, writing the defaults on the first run so they can be edited by hand
+    pub fn load() -> Self {
+        if user_file_exists(SETTINGS_FILE) {
+            load_user_file(SETTINGS_FILE)
+        } else {
+            let settings = Self::default();
+            settings.save();
+            settings
+        }
+    }
+
+    pub fn save(&self) {
+        save_user_file(SETTINGS_FILE, self);
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParticleSettings {
+    // turns off every particle effect, for low-end machines
+    pub enabled: bool,
+    // number of particles spawned per event
+    pub paddle_hit_count: u32,
+    pub wall_bounce_count: u32,
+    pub goal_count: u32,
+    // number of fading copies following each ball, `0` disables the trail
+    pub trail_length: u32,
+}
+
+impl Default for ParticleSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            paddle_hit_count: 8,
+            wall_bounce_count: 4,
+            goal_count: 24,
+            trail_length: 8,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraEffectSettings {
+    // accessibility option, turns off both screen shake and hit-stop
+    pub enabled: bool,
+    // multiplier of the screen shake strength, `0.0` disables it
+    pub shake_intensity: f32,
+    // briefly slows down time when a goal is scored or the ball is hit hard
+    pub hit_stop: bool,
+}
+
+impl Default for CameraEffectSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            shake_intensity: 1.0,
+            hit_stop: true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum WindowMode {
+    #[default]
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl WindowMode {
+    pub fn next(self) -> Self {
+        match self {
+            WindowMode::Windowed => WindowMode::Borderless,
+            WindowMode::Borderless => WindowMode::Fullscreen,
+            WindowMode::Fullscreen => WindowMode::Windowed,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplaySettings {
+    pub mode: WindowMode,
+    // name of a theme file inside `assets/themes`
+    pub theme: String,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            mode: WindowMode::default(),
+            theme: DEFAULT_THEME.to_string(),
+        }
+    }
+}
+
+// volumes are between `0.0` and `1.0`, music and effects are further scaled by the master volume
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub muted: bool,
+    // plays the music playlists in random order
+    pub shuffle: bool,
+}
+
+impl AudioSettings {
+    // volume the music is played at, taking the master volume and muting into account
+    pub fn effective_music_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master_volume * self.music_volume
+        }
+    }
+
+    // volume sound effects are played at, taking the master volume and muting into account
+    pub fn effective_sfx_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master_volume * self.sfx_volume
+        }
+    }
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            // the music stays in the background
+            music_volume: 0.25,
+            sfx_volume: 1.0,
+            muted: false,
+            shuffle: false,
+        }
+    }
+}
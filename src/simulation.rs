@@ -0,0 +1,121 @@
+use amethyst::{
+    core::{bundle::SystemBundle, Time},
+    ecs::{Dispatcher, DispatcherBuilder},
+    prelude::*,
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::{
+    bundle::PongBundle,
+    controls::{Controllers, GameRng},
+    events::GameEvent,
+    pong::{
+        initialise_ball, initialise_paddles, match_winner, MatchStats, RunState, ScoreBoard, Side,
+    },
+    rules::Rules,
+    telemetry::Telemetry,
+};
+
+// length of a single step of a headless match, in seconds
+pub const TICK: f32 = 1.0 / 60.0;
+
+// a match without window, audio or real time, advanced one fixed step at a time as fast as the
+// machine allows
+pub struct Simulation {
+    pub world: World,
+    dispatcher: Dispatcher<'static, 'static>,
+    ticks: u64,
+    reader_id: ReaderId<GameEvent>,
+    // paddle hits since the last goal
+    rally: u32,
+    // paddle hits of every finished rally
+    rallies: Vec<u32>,
+}
+
+impl Simulation {
+    // starts a match with the ball already in play
+    pub fn new(rules: Rules, controllers: Controllers, seed: Option<u64>) -> Self {
+        let mut world = World::new();
+        let mut builder = DispatcherBuilder::new();
+        PongBundle::new()
+            .without_audio()
+            .build(&mut world, &mut builder)
+            .expect("Failed to build the gameplay systems");
+        let mut dispatcher = builder.build();
+        dispatcher.setup(&mut world);
+
+        world.insert(rules);
+        world.insert(controllers);
+        world.insert(GameRng::new(seed));
+        world.insert(ScoreBoard::default());
+        world.insert(MatchStats::default());
+        world.insert(RunState::Running);
+
+        initialise_paddles(&mut world);
+        initialise_ball(&mut world);
+
+        let reader_id = world
+            .write_resource::<EventChannel<GameEvent>>()
+            .register_reader();
+        Self {
+            world,
+            dispatcher,
+            ticks: 0,
+            reader_id,
+            rally: 0,
+            rallies: Vec::new(),
+        }
+    }
+
+    // records the match into `telemetry`
+    pub fn with_telemetry(mut self, mut telemetry: Telemetry) -> Self {
+        telemetry.start_match();
+        self.world.insert(telemetry);
+        self
+    }
+
+    pub fn step(&mut self) {
+        self.world.write_resource::<Time>().set_delta_seconds(TICK);
+        self.dispatcher.dispatch(&self.world);
+        self.world.maintain();
+        self.ticks += 1;
+
+        let events = self.world.read_resource::<EventChannel<GameEvent>>();
+        for event in events.read(&mut self.reader_id) {
+            match event {
+                GameEvent::PaddleHit { .. } => self.rally += 1,
+                GameEvent::Goal { .. } => self.rallies.push(std::mem::take(&mut self.rally)),
+                GameEvent::WallBounce { .. } => {}
+            }
+        }
+    }
+
+    // paddle hits of every rally that ended in a goal so far
+    pub fn rallies(&self) -> &[u32] {
+        &self.rallies
+    }
+
+    // number of steps played so far
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn winner(&self) -> Option<Side> {
+        match_winner(&self.world)
+    }
+
+    // steps until a player wins, or gives up after `max_ticks`
+    pub fn run(&mut self, max_ticks: u64) -> Option<Side> {
+        for _ in 0..max_ticks {
+            if let Some(winner) = self.winner() {
+                return Some(winner);
+            }
+            self.step();
+        }
+        self.winner()
+    }
+
+    pub fn scores(&self) -> ScoreBoard {
+        (*self.world.read_resource::<ScoreBoard>()).clone()
+    }
+}
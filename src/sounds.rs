@@ -0,0 +1,195 @@
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    audio::{OggFormat, Source, SourceHandle},
+    ecs::{
+        shred::{ResourceId, SystemData},
+        Read, World,
+    },
+    Error,
+};
+use log::error;
+use rand::{seq::SliceRandom, Rng};
+use rodio::{source::ChannelVolume, Decoder, Device, Source as _};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, io::Cursor, path::Path};
+
+use crate::{
+    pong::{ARENA_WIDTH, BALL_VELOCITY_X, BALL_VELOCITY_Y},
+    settings::Settings,
+};
+
+// how far sounds are panned at the edges of the arena, `1.0` plays them on a single side
+const PAN_WIDTH: f32 = 0.75;
+// how much the pitch rises when the ball is twice as fast as when served
+const PITCH_PER_SPEED: f32 = 0.25;
+const MIN_PITCH: f32 = 0.8;
+const MAX_PITCH: f32 = 1.3;
+// random pitch offset, so repeated sounds are not exactly the same
+const PITCH_JITTER: f32 = 0.04;
+
+// everything in the game that makes a sound
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SoundEvent {
+    WallBounce,
+    PaddleHit,
+    Goal,
+    // a new ball enters the arena
+    Serve,
+    // a goal brings a player one point away from winning
+    MatchPoint,
+    MenuSelect,
+    // nothing plays it until the game has power-ups, but `config/sounds.ron` can already
+    // provide a sound
+    PowerUp,
+}
+
+// sound effects read from `config/sounds.ron`, each event has one or more variations that are
+// picked at random, events without any are silent
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundConfig {
+    // files are relative to `assets`
+    pub sounds: HashMap<SoundEvent, Vec<String>>,
+}
+
+impl SoundConfig {
+    // fails on the first file that does not exist inside `assets_dir`, so a typo in the config
+    // is reported at startup instead of silently playing nothing
+    pub fn check_files(&self, assets_dir: &Path) -> Result<(), Error> {
+        for (event, files) in &self.sounds {
+            for file in files {
+                let path = assets_dir.join(file);
+                if !path.is_file() {
+                    return Err(Error::from_string(format!(
+                        "Sound file {:?} for {:?} does not exist",
+                        path, event
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// `Resource` with the loaded variations of every sound event
+pub struct SoundRegistry {
+    sounds: HashMap<SoundEvent, Vec<SourceHandle>>,
+}
+
+impl SoundRegistry {
+    pub fn load(config: &SoundConfig, loader: &Loader, storage: &AssetStorage<Source>) -> Self {
+        let sounds = config
+            .sounds
+            .iter()
+            .map(|(event, files)| {
+                let handles = files
+                    .iter()
+                    .map(|file| loader.load(file.as_str(), OggFormat, (), storage))
+                    .collect();
+                (*event, handles)
+            })
+            .collect();
+        Self { sounds }
+    }
+}
+
+// audio device sound effects are played on, as a `Resource` if there is one
+pub struct SfxOutput {
+    device: Device,
+}
+
+impl SfxOutput {
+    // amethyst's `Output` can only play sounds unchanged
+    pub fn new() -> Option<Self> {
+        rodio::default_output_device().map(|device| Self { device })
+    }
+}
+
+// how a sound effect is played, so it tells where and how fast the ball was
+#[derive(Clone, Copy, Debug)]
+pub struct SoundParams {
+    // from `-1.0` for the left speaker to `1.0` for the right one
+    pub pan: f32,
+    // playback speed, higher values sound higher
+    pub pitch: f32,
+}
+
+impl Default for SoundParams {
+    fn default() -> Self {
+        Self {
+            pan: 0.0,
+            pitch: 1.0,
+        }
+    }
+}
+
+impl SoundParams {
+    // pans according to the ball's X position and pitches according to its speed
+    pub fn for_ball(x: f32, velocity: [f32; 2]) -> Self {
+        let pan = ((x / ARENA_WIDTH) * 2.0 - 1.0).clamp(-1.0, 1.0) * PAN_WIDTH;
+
+        let serve_speed = f32::hypot(BALL_VELOCITY_X, BALL_VELOCITY_Y);
+        let speed = f32::hypot(velocity[0], velocity[1]);
+        let jitter = rand::thread_rng().gen_range(-PITCH_JITTER..=PITCH_JITTER);
+        let pitch = (1.0 + (speed / serve_speed - 1.0) * PITCH_PER_SPEED + jitter)
+            .clamp(MIN_PITCH, MAX_PITCH);
+
+        Self { pan, pitch }
+    }
+}
+
+// whether sound effects are played at all, as a `Resource` set by `PongBundle`
+#[derive(Clone, Copy, Debug)]
+pub struct SfxEnabled(pub bool);
+
+impl Default for SfxEnabled {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+// everything needed to play sound effects, usable from `System`s and through
+// `World::system_data` from states, plays nothing until the audio is initialised
+#[derive(SystemData)]
+pub struct SoundPlayer<'a> {
+    enabled: Read<'a, SfxEnabled>,
+    registry: Option<Read<'a, SoundRegistry>>,
+    storage: Read<'a, AssetStorage<Source>>,
+    output: Option<Read<'a, SfxOutput>>,
+    settings: Option<Read<'a, Settings>>,
+}
+
+impl SoundPlayer<'_> {
+    // plays one of the event's variations at random, at the volume from the audio settings
+    pub fn play(&self, event: SoundEvent, params: SoundParams) {
+        if !self.enabled.0 {
+            return;
+        }
+        let (output, registry, settings) = match (&self.output, &self.registry, &self.settings) {
+            (Some(output), Some(registry), Some(settings)) => (output, registry, settings),
+            _ => return,
+        };
+        let source = match registry
+            .sounds
+            .get(&event)
+            .and_then(|variations| variations.choose(&mut rand::thread_rng()))
+            .and_then(|handle| self.storage.get(handle))
+        {
+            Some(source) => source,
+            None => return,
+        };
+
+        let decoder = match Decoder::new(Cursor::new(source.bytes.clone())) {
+            Ok(decoder) => decoder,
+            Err(e) => {
+                error!("Cannot decode the sound of {:?}: {}", event, e);
+                return;
+            }
+        };
+        let volume = settings.audio.effective_sfx_volume();
+        let left = volume * (1.0 - params.pan).min(1.0);
+        let right = volume * (1.0 + params.pan).min(1.0);
+        let sound = ChannelVolume::new(decoder.speed(params.pitch), vec![left, right]);
+        rodio::play_raw(&output.device, sound.convert_samples());
+    }
+}
@@ -0,0 +1,88 @@
+use amethyst::{
+    core::timing::Time,
+    derive::SystemDesc,
+    ecs::{Join, Read, System, SystemData, Write, WriteStorage},
+};
+
+use crate::{
+    abilities::{Abilities, AbilityState},
+    controls::{AbilityInputs, Controllers, GameMode, PaddleInputs},
+    pong::Paddle,
+    rules::Rules,
+};
+
+// starts dashes and charges smashes from `AbilityInputs`, for the paddles of players in the game
+// modes allowing them
+#[derive(SystemDesc)]
+pub struct AbilitySystem;
+
+impl<'s> System<'s> for AbilitySystem {
+    type SystemData = (
+        WriteStorage<'s, Paddle>,
+        Write<'s, Abilities>,
+        Read<'s, AbilityInputs>,
+        Read<'s, PaddleInputs>,
+        Read<'s, GameMode>,
+        Read<'s, Controllers>,
+        Read<'s, Rules>,
+        Read<'s, Time>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut paddles,
+            mut abilities,
+            inputs,
+            axes,
+            mode,
+            controllers,
+            rules,
+            time,
+        ): Self::SystemData,
+    ) {
+        let rules = &rules.abilities;
+        let delta = time.delta_seconds();
+
+        for paddle in (&mut paddles).join() {
+            let state = abilities.get_mut(paddle.side);
+            if !rules.enabled(*mode, controllers.get(paddle.side)) {
+                *state = AbilityState::default();
+                continue;
+            }
+            let input = inputs.get(paddle.side);
+            state.dash_cooldown = (state.dash_cooldown - delta).max(0.0);
+            state.smash_cooldown = (state.smash_cooldown - delta).max(0.0);
+
+            if input.dash && !state.dash_held && state.dash_cooldown <= 0.0 {
+                // dashes go where the player steers, or where the paddle is already going
+                let axis = axes.get(paddle.side);
+                let direction = if axis != 0.0 { axis } else { paddle.velocity };
+                if direction != 0.0 {
+                    paddle.velocity = rules.dash_speed * direction.signum();
+                    state.dash_cooldown = rules.dash_cooldown;
+                }
+            }
+            state.dash_held = input.dash;
+
+            if state.smash_armed() {
+                state.smash_window -= delta;
+                if !state.smash_armed() {
+                    // missing the ball wastes the smash
+                    state.smash_window = 0.0;
+                    state.smash_power = 0.0;
+                    state.smash_cooldown = rules.smash_cooldown;
+                }
+            } else if input.smash && state.smash_cooldown <= 0.0 {
+                state.smash_charge = (state.smash_charge
+                    + delta / rules.smash_charge_time.max(f32::EPSILON))
+                .min(1.0);
+            } else if state.smash_charge > 0.0 {
+                // releasing the button arms the smash
+                state.smash_power = state.smash_charge;
+                state.smash_charge = 0.0;
+                state.smash_window = rules.smash_window;
+            }
+        }
+    }
+}
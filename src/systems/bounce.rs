@@ -1,16 +1,40 @@
 use amethyst::{
-    assets::AssetStorage,
-    audio::{output::Output, Source},
-    core::Transform,
+    core::{timing::Time, Transform},
     derive::SystemDesc,
-    ecs::{Join, Read, ReadExpect, ReadStorage, System, SystemData, WriteStorage},
+    ecs::{Join, Read, ReadStorage, System, SystemData, Write, WriteStorage},
+    shrev::EventChannel,
 };
 
 use crate::{
-    audio::{play_bounce_sound, Sounds},
-    pong::{Ball, Paddle, Side, ARENA_HEIGHT},
+    abilities::Abilities,
+    events::GameEvent,
+    pong::{Ball, Paddle, Side, ARENA_HEIGHT, BALL_VELOCITY_X, BALL_VELOCITY_Y},
+    rules::Rules,
+    sounds::{SoundEvent, SoundParams, SoundPlayer},
 };
 
+// upper limit of the steps a single frame is checked in, only reached by absurdly long frames
+const MAX_STEPS: usize = 4096;
+
+// a paddle and how it moved during the frame
+struct MovingPaddle {
+    paddle: Paddle,
+    // centre at the end of the frame
+    position: [f32; 2],
+    velocity: [f32; 2],
+}
+
+impl MovingPaddle {
+    // centre of the paddle when `progress` of the frame has passed
+    fn position_at(&self, progress: f32, delta: f32) -> [f32; 2] {
+        let remaining = (1.0 - progress) * delta;
+        [
+            self.position[0] - self.velocity[0] * remaining,
+            self.position[1] - self.velocity[1] * remaining,
+        ]
+    }
+}
+
 #[derive(SystemDesc)]
 pub struct BounceSystem;
 
@@ -18,59 +42,207 @@ impl<'s> System<'s> for BounceSystem {
     type SystemData = (
         WriteStorage<'s, Ball>,
         ReadStorage<'s, Paddle>,
-        ReadStorage<'s, Transform>,
-        Read<'s, AssetStorage<Source>>,
-        ReadExpect<'s, Sounds>,
-        Option<Read<'s, Output>>,
+        WriteStorage<'s, Transform>,
+        Read<'s, Time>,
+        Read<'s, Rules>,
+        Write<'s, Abilities>,
+        SoundPlayer<'s>,
+        Write<'s, EventChannel<GameEvent>>,
     );
 
     fn run(
         &mut self,
-        (mut balls, paddles, transforms, storage, sounds, audio_output): Self::SystemData,
+        (
+            mut balls,
+            paddles,
+            mut transforms,
+            time,
+            rules,
+            mut abilities,
+            sounds,
+            mut events,
+        ): Self::SystemData,
     ) {
-        // check the velocity of the ball every time, to prevent multiple collisions
-        for (ball, transform) in (&mut balls, &transforms).join() {
-            let ball_x = transform.translation().x;
-            let ball_y = transform.translation().y;
+        let delta = time.delta_seconds();
+        // the paddles are collected first, as the balls' transforms are changed below
+        let paddles = (&paddles, &transforms)
+            .join()
+            .map(|(paddle, transform)| MovingPaddle {
+                paddle: paddle.clone(),
+                position: [transform.translation().x, transform.translation().y],
+                velocity: [paddle.velocity_x, paddle.velocity],
+            })
+            .collect::<Vec<_>>();
+        let fastest_paddle = paddles
+            .iter()
+            .map(|paddle| f32::hypot(paddle.velocity[0], paddle.velocity[1]))
+            .fold(0.0, f32::max);
 
-            // bounce at the top or the bottom of the arena
-            if (ball_y < ball.radius && ball.velocity[1] < 0.0)
-                || (ball_y >= ARENA_HEIGHT - ball.radius && ball.velocity[1] > 0.0)
-            {
-                ball.velocity[1] = -ball.velocity[1];
-                play_bounce_sound(&*sounds, &storage, audio_output.as_deref());
-            }
+        for (ball, transform) in (&mut balls, &mut transforms).join() {
+            // the ball's movement this frame is replayed in steps shorter than its radius, so
+            // fast balls, fast paddles or long frames cannot skip over a paddle or a wall
+            let distance =
+                (f32::hypot(ball.velocity[0], ball.velocity[1]) + fastest_paddle) * delta;
+            let steps = ((distance / (ball.radius * 0.5)).ceil() as usize).clamp(1, MAX_STEPS);
+            let step = delta / steps as f32;
+            let mut position = [
+                transform.translation().x - ball.velocity[0] * delta,
+                transform.translation().y - ball.velocity[1] * delta,
+            ];
+            // the ball is only moved when it hit something, to keep its position exact otherwise
+            let mut changed = false;
+
+            for index in 0..steps {
+                let previous = position;
+                position[0] += ball.velocity[0] * step;
+                position[1] += ball.velocity[1] * step;
+
+                for moving in paddles.iter() {
+                    let paddle = &moving.paddle;
+                    let before = moving.position_at(index as f32 / steps as f32, delta);
+                    let after = moving.position_at((index + 1) as f32 / steps as f32, delta);
+                    // the paddle is grown by the ball's radius, as the ball's anchor point is its
+                    // centre, so the ball touches the paddle when its centre is inside
+                    let half = [
+                        paddle.width * 0.5 + ball.radius,
+                        paddle.height * 0.5 + ball.radius,
+                    ];
+                    let offset = [position[0] - after[0], position[1] - after[1]];
+                    if offset[0].abs() > half[0] || offset[1].abs() > half[1] {
+                        continue;
+                    }
+                    // direction the paddle's front faces
+                    let front = match paddle.side {
+                        Side::Left => 1.0,
+                        Side::Right => -1.0,
+                    };
+                    let (axis, normal) = if rules.paddle.horizontal {
+                        let previous_offset = [previous[0] - before[0], previous[1] - before[1]];
+                        let axis = touched_axis(previous_offset, offset, half);
+                        // the side of the paddle the ball came from, the front if it is unclear
+                        let from = if previous_offset[axis].abs() > half[axis] {
+                            previous_offset[axis]
+                        } else {
+                            offset[axis]
+                        };
+                        let normal = if from != 0.0 {
+                            from.signum()
+                        } else if axis == 0 {
+                            front
+                        } else {
+                            1.0
+                        };
+                        (axis, normal)
+                    } else {
+                        // paddles stuck to their goal line return every ball they touch off their
+                        // front, as in the classic game
+                        (0, front)
+                    };
+
+                    position[axis] = after[axis] + normal * half[axis];
+                    changed = true;
+                    // the ball only bounces when it moves towards the paddle, seen from the paddle
+                    if (ball.velocity[axis] - moving.velocity[axis]) * normal >= 0.0 {
+                        continue;
+                    }
+                    ball.velocity[axis] = 2.0 * moving.velocity[axis] - ball.velocity[axis];
+                    if axis == 0 {
+                        // a moving paddle drags the ball along and gives it spin
+                        ball.spin = paddle.velocity * rules.paddle.spin;
+                        ball.add_spin_velocity(ball.spin);
+                    }
+                    // an armed smash launches the ball faster, the more charged the faster
+                    let state = abilities.get_mut(paddle.side);
+                    if state.smash_armed() {
+                        let boost = 1.0 + (rules.abilities.smash_boost - 1.0) * state.smash_power;
+                        ball.velocity = [ball.velocity[0] * boost, ball.velocity[1] * boost];
+                        // smashing an already smashed ball cannot speed it up any further
+                        let limit = f32::hypot(BALL_VELOCITY_X, BALL_VELOCITY_Y)
+                            * rules.abilities.smash_boost.max(1.0);
+                        let speed = f32::hypot(ball.velocity[0], ball.velocity[1]);
+                        if speed > limit {
+                            ball.velocity = [
+                                ball.velocity[0] * limit / speed,
+                                ball.velocity[1] * limit / speed,
+                            ];
+                        }
+                        state.smash_window = 0.0;
+                        state.smash_power = 0.0;
+                        state.smash_cooldown = rules.abilities.smash_cooldown;
+                    }
 
-            // bounce at the paddles
-            for (paddle, paddle_transform) in (&paddles, &transforms).join() {
-                let paddle_x = paddle_transform.translation().x - (paddle.width * 0.5);
-                let paddle_y = paddle_transform.translation().y - (paddle.height * 0.5);
+                    sounds.play(
+                        SoundEvent::PaddleHit,
+                        SoundParams::for_ball(position[0], ball.velocity),
+                    );
+                    events.single_write(GameEvent::PaddleHit {
+                        side: paddle.side,
+                        position,
+                        speed: f32::hypot(ball.velocity[0], ball.velocity[1]),
+                        offset: (position[1] - after[1]) / (paddle.height * 0.5),
+                    });
+                }
 
-                // check whether the ball is touching the paddle
-                // for this, the bounding rectangle is a bit larger than the paddle, as
-                // the ball's anchor point is its center, so the created rectangle is
-                // enlarged by the ball's radius in order to check when they are touching
-                if point_in_rect(
-                    ball_x,
-                    ball_y,
-                    paddle_x - ball.radius,
-                    paddle_y - ball.radius,
-                    paddle_x + paddle.width + ball.radius,
-                    paddle_y + paddle.height + ball.radius,
-                ) {
-                    if (paddle.side == Side::Left && ball.velocity[0] < 0.0)
-                        || (paddle.side == Side::Right && ball.velocity[0] > 0.0)
-                    {
-                        ball.velocity[0] = -ball.velocity[0];
-                        play_bounce_sound(&*sounds, &storage, audio_output.as_deref());
+                // bounce at the top or the bottom of the arena
+                let (bottom, top) = (ball.radius, ARENA_HEIGHT - ball.radius);
+                if position[1] < bottom || position[1] > top {
+                    let wall = if position[1] < bottom { bottom } else { top };
+                    changed = true;
+                    let towards_wall = (position[1] < bottom && ball.velocity[1] < 0.0)
+                        || (position[1] > top && ball.velocity[1] > 0.0);
+                    if !towards_wall {
+                        // pushed into the wall by a paddle
+                        position[1] = wall;
+                        continue;
                     }
+                    position[1] = 2.0 * wall - position[1];
+                    ball.velocity[1] = -ball.velocity[1];
+                    sounds.play(
+                        SoundEvent::WallBounce,
+                        SoundParams::for_ball(position[0], ball.velocity),
+                    );
+                    events.single_write(GameEvent::WallBounce {
+                        position,
+                        speed: f32::hypot(ball.velocity[0], ball.velocity[1]),
+                    });
                 }
             }
+
+            if changed {
+                transform.set_translation_x(position[0]);
+                transform.set_translation_y(position[1]);
+            }
         }
     }
 }
 
-// returns `true` if the point is inside the provided rectangle
-fn point_in_rect(x: f32, y: f32, left: f32, bottom: f32, right: f32, top: f32) -> bool {
-    x >= left && x <= right && y >= bottom && y <= top
+// whether the ball touched a vertical side of the paddle (`0`) or its top or bottom (`1`), from
+// the ball's offsets from the paddle before and after a step, and the paddle's half size grown
+// by the ball's radius
+fn touched_axis(previous: [f32; 2], current: [f32; 2], half: [f32; 2]) -> usize {
+    let outside = |axis: usize| previous[axis].abs() > half[axis];
+    // how far through the step the ball crossed the sides of an axis
+    let entry = |axis: usize| {
+        (previous[axis].abs() - half[axis]) / (previous[axis].abs() - current[axis].abs())
+    };
+    match (outside(0), outside(1)) {
+        // came in over a corner, the sides crossed last are the ones touched
+        (true, true) => {
+            if entry(0) >= entry(1) {
+                0
+            } else {
+                1
+            }
+        }
+        (true, false) => 0,
+        (false, true) => 1,
+        // already inside, it leaves where the way out is shortest
+        (false, false) => {
+            if half[0] - current[0].abs() <= half[1] - current[1].abs() {
+                0
+            } else {
+                1
+            }
+        }
+    }
 }
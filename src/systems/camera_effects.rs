@@ -0,0 +1,147 @@
+use amethyst::{
+    core::{timing::Time, Transform},
+    derive::SystemDesc,
+    ecs::{
+        Component, DenseVecStorage, Join, Read, ReadExpect, ReadStorage, System, SystemData, Write,
+        WriteStorage,
+    },
+    shrev::{EventChannel, ReaderId},
+};
+use rand::Rng;
+
+use crate::{
+    events::GameEvent,
+    pong::{GameSpeed, RunState, BALL_VELOCITY_X, BALL_VELOCITY_Y},
+    settings::Settings,
+};
+
+// largest camera offset in arena units, reached at full trauma and intensity
+const MAX_SHAKE_OFFSET: f32 = 3.0;
+// trauma lost per second
+const TRAUMA_DECAY: f32 = 1.5;
+const GOAL_TRAUMA: f32 = 0.8;
+// paddle hits this much faster than the serve shake the screen, the faster the stronger
+const FAST_HIT_FACTOR: f32 = 1.3;
+// paddle hits adding more trauma than this also trigger a hit-stop
+const HIT_STOP_TRAUMA: f32 = 0.3;
+// real seconds the hit-stop lasts and how much time is slowed down meanwhile
+const GOAL_HIT_STOP: f32 = 0.2;
+const PADDLE_HIT_STOP: f32 = 0.06;
+const HIT_STOP_TIME_SCALE: f32 = 0.1;
+
+// slowest paddle hit that shakes the screen
+fn fast_hit_speed() -> f32 {
+    f32::hypot(BALL_VELOCITY_X, BALL_VELOCITY_Y) * FAST_HIT_FACTOR
+}
+
+// marks the camera that gets shaken, `origin` is where it rests
+pub struct CameraShake {
+    pub origin: [f32; 2],
+}
+
+impl Component for CameraShake {
+    type Storage = DenseVecStorage<Self>;
+}
+
+// shakes the camera and slows down time in reaction to gameplay events
+#[derive(SystemDesc)]
+#[system_desc(name(CameraEffectsSystemDesc))]
+pub struct CameraEffectsSystem {
+    #[system_desc(event_channel_reader)]
+    reader_id: ReaderId<GameEvent>,
+    // between `0.0` and `1.0`, the shake is proportional to its square
+    #[system_desc(skip)]
+    trauma: f32,
+    // real seconds left from the current hit-stop
+    #[system_desc(skip)]
+    hit_stop: f32,
+}
+
+impl CameraEffectsSystem {
+    pub fn new(reader_id: ReaderId<GameEvent>) -> Self {
+        Self {
+            reader_id,
+            trauma: 0.0,
+            hit_stop: 0.0,
+        }
+    }
+}
+
+impl<'s> System<'s> for CameraEffectsSystem {
+    type SystemData = (
+        ReadStorage<'s, CameraShake>,
+        WriteStorage<'s, Transform>,
+        Read<'s, EventChannel<GameEvent>>,
+        Write<'s, Time>,
+        Read<'s, RunState>,
+        ReadExpect<'s, Settings>,
+        Read<'s, GameSpeed>,
+    );
+
+    fn run(
+        &mut self,
+        (shakes, mut transforms, events, mut time, run_state, settings, speed): Self::SystemData,
+    ) {
+        let events = events
+            .read(&mut self.reader_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        let settings = &settings.camera_effects;
+
+        // never leave the game slowed down or the camera displaced outside of a running match
+        if *run_state != RunState::Running || !settings.enabled {
+            self.trauma = 0.0;
+            self.hit_stop = 0.0;
+        } else {
+            for event in events {
+                match event {
+                    GameEvent::Goal { .. } => {
+                        self.trauma += GOAL_TRAUMA;
+                        self.hit_stop = self.hit_stop.max(GOAL_HIT_STOP);
+                    }
+                    GameEvent::PaddleHit { speed, .. } if speed > fast_hit_speed() => {
+                        let trauma = (speed - fast_hit_speed()) / fast_hit_speed();
+                        self.trauma += trauma;
+                        if trauma > HIT_STOP_TRAUMA {
+                            self.hit_stop = self.hit_stop.max(PADDLE_HIT_STOP);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            self.trauma = self.trauma.min(1.0);
+
+            // effects are measured in real time, as the game time itself is being slowed down
+            let delta = time.delta_real_seconds();
+            self.trauma = (self.trauma - TRAUMA_DECAY * delta).max(0.0);
+            self.hit_stop = (self.hit_stop - delta).max(0.0);
+            if !settings.hit_stop {
+                self.hit_stop = 0.0;
+            }
+        }
+
+        let time_scale = if self.hit_stop > 0.0 {
+            speed.0 * HIT_STOP_TIME_SCALE
+        } else {
+            speed.0
+        };
+        if (time.time_scale() - time_scale).abs() > f32::EPSILON {
+            time.set_time_scale(time_scale);
+        }
+
+        let mut rng = rand::thread_rng();
+        let offset = MAX_SHAKE_OFFSET * settings.shake_intensity * self.trauma * self.trauma;
+        for (shake, transform) in (&shakes, &mut transforms).join() {
+            let (x, y) = if offset > 0.0 {
+                (
+                    shake.origin[0] + offset * rng.gen_range(-1.0..1.0),
+                    shake.origin[1] + offset * rng.gen_range(-1.0..1.0),
+                )
+            } else {
+                (shake.origin[0], shake.origin[1])
+            };
+            transform.set_translation_x(x);
+            transform.set_translation_y(y);
+        }
+    }
+}
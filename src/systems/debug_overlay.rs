@@ -0,0 +1,188 @@
+use amethyst::{
+    core::{
+        math::{Point2, Point3, Vector3},
+        Time, Transform,
+    },
+    derive::SystemDesc,
+    ecs::{Entities, Entity, Join, Read, ReadStorage, System, SystemData, Write, WriteStorage},
+    input::{InputHandler, StringBindings},
+    renderer::{debug_drawing::DebugLines, palette::Srgba},
+    ui::{Anchor, LineMode, UiText, UiTransform},
+    utils::fps_counter::FpsCounter,
+};
+use std::fmt::Write as _;
+
+use crate::{
+    pong::{Ball, Paddle},
+    systems::Particle,
+    theme::ThemeAssets,
+};
+
+// velocity vectors are drawn as the distance travelled in this many seconds
+const VELOCITY_SCALE: f32 = 0.2;
+const DEBUG_Z: f32 = 0.5;
+const PANEL_FONT_SIZE: f32 = 14.;
+
+fn box_color() -> Srgba {
+    Srgba::new(0.0, 1.0, 0.0, 1.0)
+}
+
+fn velocity_color() -> Srgba {
+    Srgba::new(1.0, 0.3, 0.3, 1.0)
+}
+
+// shows what the gameplay `System`s see, toggled with the `toggle_debug_overlay` action: the
+// collision bounds `BounceSystem` tests the balls against, velocity vectors, frame timings,
+// entity counts and the components of the balls and paddles
+#[derive(Default, SystemDesc)]
+pub struct DebugOverlaySystem {
+    enabled: bool,
+    // whether the action was held down last frame, to react to presses only once
+    was_pressed: bool,
+    // text listing the statistics, only exists while the overlay is shown
+    panel: Option<Entity>,
+}
+
+impl<'s> System<'s> for DebugOverlaySystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Read<'s, InputHandler<StringBindings>>,
+        Read<'s, Time>,
+        Read<'s, FpsCounter>,
+        Write<'s, DebugLines>,
+        Entities<'s>,
+        ReadStorage<'s, Ball>,
+        ReadStorage<'s, Paddle>,
+        ReadStorage<'s, Particle>,
+        ReadStorage<'s, Transform>,
+        WriteStorage<'s, UiTransform>,
+        WriteStorage<'s, UiText>,
+        Option<Read<'s, ThemeAssets>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            input,
+            time,
+            fps,
+            mut lines,
+            entities,
+            balls,
+            paddles,
+            particles,
+            transforms,
+            mut ui_transforms,
+            mut ui_texts,
+            theme,
+        ): Self::SystemData,
+    ) {
+        let pressed = input
+            .action_is_down("toggle_debug_overlay")
+            .unwrap_or(false);
+        if pressed && !self.was_pressed {
+            self.enabled = !self.enabled;
+        }
+        self.was_pressed = pressed;
+
+        // the panel is deleted along with everything else when a match ends
+        if self.panel.is_some_and(|panel| !entities.is_alive(panel)) {
+            self.panel = None;
+        }
+        if !self.enabled {
+            if let Some(panel) = self.panel.take() {
+                entities
+                    .delete(panel)
+                    .expect("Failed to delete debug panel");
+            }
+            return;
+        }
+
+        let mut text = String::new();
+        let _ = writeln!(
+            text,
+            "FPS: {:.0}  frame: {:.1} ms",
+            fps.sampled_fps(),
+            time.delta_real_seconds() * 1000.0
+        );
+        let _ = writeln!(
+            text,
+            "entities: {}  balls: {}  paddles: {}  particles: {}",
+            (&entities).join().count(),
+            (&balls).join().count(),
+            (&paddles).join().count(),
+            (&particles).join().count()
+        );
+
+        // the same bounds as in `BounceSystem`, the paddles grown by the ball's radius
+        let radius = (&balls).join().map(|ball| ball.radius).fold(0.0, f32::max);
+        for (paddle, transform) in (&paddles, &transforms).join() {
+            let position = transform.translation();
+            let half_width = paddle.width * 0.5 + radius;
+            let half_height = paddle.height * 0.5 + radius;
+            lines.draw_rectangle(
+                Point2::new(position.x - half_width, position.y - half_height),
+                Point2::new(position.x + half_width, position.y + half_height),
+                DEBUG_Z,
+                box_color(),
+            );
+            let _ = writeln!(
+                text,
+                "{:?} paddle: pos ({:.1}, {:.1})  size {:.1}x{:.1}",
+                paddle.side, position.x, position.y, paddle.width, paddle.height
+            );
+        }
+
+        for (ball, transform) in (&balls, &transforms).join() {
+            let position = transform.translation();
+            let center = Point3::new(position.x, position.y, DEBUG_Z);
+            lines.draw_circle(center, ball.radius, 16, box_color());
+            lines.draw_direction(
+                center,
+                Vector3::new(ball.velocity[0], ball.velocity[1], 0.0) * VELOCITY_SCALE,
+                velocity_color(),
+            );
+            let _ = writeln!(
+                text,
+                "ball: pos ({:.1}, {:.1})  vel ({:.1}, {:.1})  radius {:.1}",
+                position.x, position.y, ball.velocity[0], ball.velocity[1], ball.radius
+            );
+        }
+
+        if self.panel.is_none() {
+            let theme = match theme {
+                Some(theme) => theme,
+                None => return,
+            };
+            let transform = UiTransform::new(
+                "debug_panel".to_string(),
+                Anchor::TopLeft,
+                Anchor::TopLeft,
+                10.,
+                -10.,
+                10.,
+                600.,
+                300.,
+            );
+            let panel = entities
+                .build_entity()
+                .with(transform, &mut ui_transforms)
+                .with(
+                    UiText::new(
+                        theme.font.clone(),
+                        String::new(),
+                        [1.0, 1.0, 0.0, 1.0],
+                        PANEL_FONT_SIZE,
+                        LineMode::Wrap,
+                        Anchor::TopLeft,
+                    ),
+                    &mut ui_texts,
+                )
+                .build();
+            self.panel = Some(panel);
+        }
+        if let Some(ui_text) = self.panel.and_then(|panel| ui_texts.get_mut(panel)) {
+            ui_text.text = text;
+        }
+    }
+}
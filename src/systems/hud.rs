@@ -0,0 +1,260 @@
+use amethyst::{
+    core::Time,
+    derive::SystemDesc,
+    ecs::{
+        Entities, Entity, Read, ReadExpect, ReadStorage, System, SystemData, Write, WriteStorage,
+    },
+    shrev::{EventChannel, ReaderId},
+    ui::{UiFinder, UiText, UiTransform},
+};
+
+use crate::{
+    abilities::{Abilities, AbilityState},
+    controls::{Controllers, GameMode},
+    events::GameEvent,
+    pong::{MatchStats, Players, RunState, ScoreBoard, Side},
+    rules::Rules,
+    systems::ScaledUi,
+    theme::ThemeAssets,
+};
+
+// labels of the HUD prefab, `assets/ui/hud.ron`, found by their ids
+struct HudLabels {
+    score_left: Entity,
+    score_right: Entity,
+    name_left: Entity,
+    name_right: Entity,
+    clock: Entity,
+    banner: Entity,
+    rally: Entity,
+    abilities_left: Entity,
+    abilities_right: Entity,
+}
+
+impl HudLabels {
+    fn find(finder: &UiFinder<'_>) -> Option<Self> {
+        Some(Self {
+            score_left: finder.find("hud_score_left")?,
+            score_right: finder.find("hud_score_right")?,
+            name_left: finder.find("hud_name_left")?,
+            name_right: finder.find("hud_name_right")?,
+            clock: finder.find("hud_clock")?,
+            banner: finder.find("hud_banner")?,
+            rally: finder.find("hud_rally")?,
+            abilities_left: finder.find("hud_abilities_left")?,
+            abilities_right: finder.find("hud_abilities_right")?,
+        })
+    }
+
+    fn all(&self) -> [Entity; 9] {
+        [
+            self.score_left,
+            self.score_right,
+            self.name_left,
+            self.name_right,
+            self.clock,
+            self.banner,
+            self.rally,
+            self.abilities_left,
+            self.abilities_right,
+        ]
+    }
+}
+
+fn set_text(texts: &mut WriteStorage<'_, UiText>, entity: Entity, text: String) {
+    if let Some(ui_text) = texts.get_mut(entity) {
+        if ui_text.text != text {
+            ui_text.text = text;
+        }
+    }
+}
+
+// cooldowns of a paddle's dash and smash, or how far the smash is charged
+fn ability_text(state: &AbilityState) -> String {
+    let cooldown = |seconds: f32| {
+        if seconds > 0.0 {
+            format!("{:.1}s", seconds)
+        } else {
+            "ready".to_string()
+        }
+    };
+    let smash = if state.smash_armed() {
+        "ARMED".to_string()
+    } else if state.smash_charge > 0.0 {
+        format!("{:.0}%", state.smash_charge * 100.0)
+    } else {
+        cooldown(state.smash_cooldown)
+    };
+    format!("Dash {}  Smash {}", cooldown(state.dash_cooldown), smash)
+}
+
+// keeps the match statistics up to date and shows them, along with the scores, on the labels of
+// the HUD prefab
+#[derive(SystemDesc)]
+#[system_desc(name(HudSystemDesc))]
+pub struct HudSystem {
+    #[system_desc(event_channel_reader)]
+    reader_id: ReaderId<GameEvent>,
+    // `None` until the prefab of the current match has finished loading
+    #[system_desc(skip)]
+    labels: Option<HudLabels>,
+}
+
+impl HudSystem {
+    fn new(reader_id: ReaderId<GameEvent>) -> Self {
+        Self {
+            reader_id,
+            labels: None,
+        }
+    }
+}
+
+impl<'s> System<'s> for HudSystem {
+    type SystemData = (
+        Read<'s, EventChannel<GameEvent>>,
+        Write<'s, MatchStats>,
+        Read<'s, ScoreBoard>,
+        ReadExpect<'s, Players>,
+        ReadExpect<'s, Rules>,
+        Read<'s, Abilities>,
+        Read<'s, GameMode>,
+        Read<'s, Controllers>,
+        Read<'s, RunState>,
+        Read<'s, Time>,
+        Entities<'s>,
+        UiFinder<'s>,
+        ReadStorage<'s, UiTransform>,
+        WriteStorage<'s, UiText>,
+        WriteStorage<'s, ScaledUi>,
+        Option<Read<'s, ThemeAssets>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            events,
+            mut stats,
+            scores,
+            players,
+            rules,
+            abilities,
+            mode,
+            controllers,
+            run_state,
+            time,
+            entities,
+            finder,
+            transforms,
+            mut texts,
+            mut layouts,
+            theme,
+        ): Self::SystemData,
+    ) {
+        for event in events.read(&mut self.reader_id) {
+            match event {
+                GameEvent::PaddleHit { .. } => stats.rally += 1,
+                // the player who conceded serves the next ball
+                GameEvent::Goal { scorer, .. } => {
+                    stats.rally = 0;
+                    stats.server = scorer.opposite();
+                }
+                GameEvent::WallBounce { .. } => {}
+            }
+        }
+        if *run_state == RunState::Running {
+            stats.elapsed += time.delta_seconds();
+        }
+
+        // the labels are deleted along with the rest of the match
+        if self
+            .labels
+            .as_ref()
+            .is_some_and(|labels| !entities.is_alive(labels.score_left))
+        {
+            self.labels = None;
+        }
+        if self.labels.is_none() {
+            // prefabs are loaded in the background, the labels appear a few frames after it
+            self.labels = HudLabels::find(&finder);
+            if let Some(labels) = &self.labels {
+                // the labels follow the window size and the theme like every other text
+                for entity in labels.all().iter() {
+                    if let (Some(transform), Some(text)) =
+                        (transforms.get(*entity), texts.get_mut(*entity))
+                    {
+                        if let Some(theme) = &theme {
+                            text.font = theme.font.clone();
+                            text.color = theme.theme.text_color;
+                        }
+                        layouts
+                            .insert(*entity, ScaledUi::new(transform, text.font_size))
+                            .expect("Failed to scale HUD label");
+                    }
+                }
+            }
+        }
+        let labels = match &self.labels {
+            Some(labels) => labels,
+            None => return,
+        };
+
+        set_text(&mut texts, labels.score_left, scores.score_left.to_string());
+        set_text(
+            &mut texts,
+            labels.score_right,
+            scores.score_right.to_string(),
+        );
+
+        // the server's name is marked
+        let (left_marker, right_marker) = match stats.server {
+            Side::Left => ("> ", ""),
+            Side::Right => ("", " <"),
+        };
+        set_text(
+            &mut texts,
+            labels.name_left,
+            format!("{}{}", left_marker, players.left),
+        );
+        set_text(
+            &mut texts,
+            labels.name_right,
+            format!("{}{}", players.right, right_marker),
+        );
+
+        let seconds = stats.elapsed as u32;
+        set_text(
+            &mut texts,
+            labels.clock,
+            format!("{}:{:02}", seconds / 60, seconds % 60),
+        );
+
+        let match_point = scores.score_left.max(scores.score_right) == rules.winning_score - 1;
+        set_text(
+            &mut texts,
+            labels.banner,
+            if match_point { "MATCH POINT" } else { "" }.to_string(),
+        );
+
+        let rally = if stats.rally > 0 {
+            format!("Rally: {}", stats.rally)
+        } else {
+            String::new()
+        };
+        set_text(&mut texts, labels.rally, rally);
+
+        for (side, entity) in [
+            (Side::Left, labels.abilities_left),
+            (Side::Right, labels.abilities_right),
+        ]
+        .iter()
+        {
+            // computer players and bots have no abilities to show
+            let text = if rules.abilities.enabled(*mode, controllers.get(*side)) {
+                ability_text(abilities.get(*side))
+            } else {
+                String::new()
+            };
+            set_text(&mut texts, *entity, text);
+        }
+    }
+}
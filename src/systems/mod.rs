@@ -1,9 +1,33 @@
+pub use self::abilities::AbilitySystem;
 pub use self::bounce::BounceSystem;
+pub use self::camera_effects::{CameraEffectsSystemDesc, CameraShake};
+pub use self::debug_overlay::DebugOverlaySystem;
+pub use self::hud::HudSystemDesc;
 pub use self::move_balls::MoveBallsSystem;
+pub use self::music::MusicSystem;
 pub use self::paddle::PaddleSystem;
+pub use self::paddle_input::PaddleInputSystem;
+pub use self::particles::{Particle, ParticleSystemDesc};
+pub use self::replay::ReplayRecorderSystem;
+pub use self::telemetry::TelemetrySystemDesc;
+pub use self::theme::ThemeSystem;
+pub use self::viewport::{ScaledUi, ViewportSystem};
+pub use self::window_mode::WindowModeSystem;
 pub use self::winner::WinnerSystem;
 
+mod abilities;
 mod bounce;
+mod camera_effects;
+mod debug_overlay;
+mod hud;
 mod move_balls;
+mod music;
 mod paddle;
+mod paddle_input;
+mod particles;
+mod replay;
+mod telemetry;
+mod theme;
+mod viewport;
+mod window_mode;
 mod winner;
@@ -2,24 +2,32 @@ use amethyst::{
     core::timing::Time,
     core::transform::Transform,
     derive::SystemDesc,
-    ecs::{Join, Read, ReadStorage, System, SystemData, WriteStorage},
+    ecs::{Join, Read, System, SystemData, WriteStorage},
 };
 
-use crate::pong::Ball;
+use crate::{pong::Ball, rules::Rules};
+
+// share of the ball's spin lost per second
+const SPIN_DECAY: f32 = 1.5;
 
 #[derive(SystemDesc)]
 pub struct MoveBallsSystem;
 
 impl<'s> System<'s> for MoveBallsSystem {
     type SystemData = (
-        ReadStorage<'s, Ball>,
+        WriteStorage<'s, Ball>,
         WriteStorage<'s, Transform>,
         Read<'s, Time>,
+        Read<'s, Rules>,
     );
 
-    fn run(&mut self, (balls, mut transforms, time): Self::SystemData) {
+    fn run(&mut self, (mut balls, mut transforms, time, rules): Self::SystemData) {
         // move every ball according to its speed and time elapsed
-        for (ball, transform) in (&balls, &mut transforms).join() {
+        for (ball, transform) in (&mut balls, &mut transforms).join() {
+            // spin pulls the ball's path towards the direction the paddle moved in, and wears off
+            let spin = ball.spin * rules.paddle.curve * time.delta_seconds();
+            ball.add_spin_velocity(spin);
+            ball.spin *= (-SPIN_DECAY * time.delta_seconds()).exp();
             transform.prepend_translation_x(ball.velocity[0] * time.delta_seconds());
             transform.prepend_translation_y(ball.velocity[1] * time.delta_seconds());
         }
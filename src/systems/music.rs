@@ -0,0 +1,146 @@
+use amethyst::{
+    assets::AssetStorage,
+    audio::{output::Output, AudioSink, Source, SourceHandle},
+    core::Time,
+    derive::SystemDesc,
+    ecs::{Read, ReadExpect, System, SystemData, WriteExpect},
+    input::{InputHandler, StringBindings},
+};
+use log::error;
+
+use crate::{
+    audio::{Music, PlaylistKind},
+    settings::Settings,
+};
+
+// the track being played, in its own sink so it can fade independently of the others
+struct Track {
+    sink: AudioSink,
+    playlist: PlaylistKind,
+    // track waiting for the sink to run empty or for its asset to finish loading
+    pending: Option<SourceHandle>,
+    // seconds since the track started fading in
+    fade_in: f32,
+}
+
+// how loud a track is after fading for `seconds`, between `0.0` and `1.0`
+fn fade_level(seconds: f32, duration: f32) -> f32 {
+    if duration > 0.0 {
+        (seconds / duration).clamp(0.0, 1.0)
+    } else if seconds > 0.0 {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+// plays the active playlist and crossfades whenever it changes or a track is skipped with the
+// `next_track` and `previous_track` actions, the music's volume follows the audio settings
+#[derive(Default, SystemDesc)]
+pub struct MusicSystem {
+    current: Option<Track>,
+    // tracks fading out, with the seconds left until they are silent
+    fading: Vec<(AudioSink, f32)>,
+    // whether the actions were held down last frame, to react to presses only once
+    was_next_pressed: bool,
+    was_previous_pressed: bool,
+}
+
+impl MusicSystem {
+    // fades out the current track and starts `source` in its place
+    fn switch(
+        &mut self,
+        output: &Output,
+        playlist: PlaylistKind,
+        source: Option<SourceHandle>,
+        duration: f32,
+    ) {
+        if let Some(track) = self.current.take() {
+            // a track that has not fully faded in yet fades out from where it is
+            let remaining = fade_level(track.fade_in, duration) * duration;
+            self.fading.push((track.sink, remaining));
+        }
+        self.current = Some(Track {
+            sink: AudioSink::new(output),
+            playlist,
+            pending: source,
+            fade_in: 0.0,
+        });
+    }
+}
+
+impl<'s> System<'s> for MusicSystem {
+    type SystemData = (
+        Option<Read<'s, Output>>,
+        Read<'s, AssetStorage<Source>>,
+        Option<WriteExpect<'s, Music>>,
+        ReadExpect<'s, Settings>,
+        Read<'s, InputHandler<StringBindings>>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (output, storage, music, settings, input, time): Self::SystemData) {
+        let next_pressed = input.action_is_down("next_track").unwrap_or(false);
+        let previous_pressed = input.action_is_down("previous_track").unwrap_or(false);
+        let skip_next = next_pressed && !self.was_next_pressed;
+        let skip_previous = previous_pressed && !self.was_previous_pressed;
+        self.was_next_pressed = next_pressed;
+        self.was_previous_pressed = previous_pressed;
+
+        // there is nothing to play without an audio device or before the audio is initialised
+        let (output, mut music) = match (output, music) {
+            (Some(output), Some(music)) => (output, music),
+            _ => return,
+        };
+
+        let shuffle = settings.audio.shuffle;
+        let active = music.active;
+        let duration = music.crossfade.max(0.0);
+        // fades keep their pace during hit-stop
+        let delta = time.delta_real_seconds();
+
+        let playlist_changed = self
+            .current
+            .as_ref()
+            .is_none_or(|track| track.playlist != active);
+        if playlist_changed || skip_next {
+            let source = music.playlist_mut(active).next(shuffle);
+            self.switch(&output, active, source, duration);
+        } else if skip_previous {
+            let source = music.playlist_mut(active).previous();
+            self.switch(&output, active, source, duration);
+        }
+
+        let volume = settings.audio.effective_music_volume();
+        if let Some(track) = &mut self.current {
+            // the playlist goes on when a track ends, without a fade
+            if track.sink.empty() && track.pending.is_none() {
+                track.pending = music.playlist_mut(track.playlist).next(shuffle);
+            }
+            // tracks are loaded in the background, the pending one might not be ready yet
+            if track.sink.empty() {
+                if let Some(source) = track
+                    .pending
+                    .as_ref()
+                    .and_then(|handle| storage.get(handle))
+                {
+                    if let Err(e) = track.sink.append(source) {
+                        error!("Cannot play music track: {}", e);
+                    }
+                    track.pending = None;
+                }
+            }
+            track.fade_in += delta;
+            track
+                .sink
+                .set_volume(volume * fade_level(track.fade_in, duration));
+        }
+
+        for (sink, remaining) in self.fading.iter_mut() {
+            *remaining -= delta;
+            sink.set_volume(volume * fade_level(*remaining, duration));
+        }
+        // dropping a sink stops its track
+        self.fading.retain(|(_, remaining)| *remaining > 0.0);
+    }
+}
@@ -1,11 +1,14 @@
 use amethyst::{
-    core::Transform,
+    core::{timing::Time, Transform},
     derive::SystemDesc,
-    ecs::{Join, Read, ReadStorage, System, SystemData, WriteStorage},
-    input::{InputHandler, StringBindings},
+    ecs::{Join, Read, System, SystemData, WriteStorage},
 };
 
-use crate::pong::{Paddle, Side, ARENA_HEIGHT, PADDLE_HEIGHT};
+use crate::{
+    controls::{HorizontalInputs, PaddleInputs},
+    pong::{Paddle, ARENA_HEIGHT},
+    rules::{PaddlePhysics, Rules},
+};
 
 // `SystemDesc` provides a recipe for how to instantiate this `System`
 #[derive(SystemDesc)]
@@ -16,31 +19,65 @@ impl<'s> System<'s> for PaddleSystem {
     type SystemData = (
         // it mutates `Transform` components
         WriteStorage<'s, Transform>,
-        // it reads `Paddle` components
-        ReadStorage<'s, Paddle>,
-        // accesses current inputs
-        Read<'s, InputHandler<StringBindings>>,
+        // it changes the velocity of `Paddle` components
+        WriteStorage<'s, Paddle>,
+        // accesses current inputs, whichever controller they come from
+        Read<'s, PaddleInputs>,
+        Read<'s, HorizontalInputs>,
+        Read<'s, Rules>,
+        Read<'s, Time>,
     );
 
-    fn run(&mut self, (mut transforms, paddles, input): Self::SystemData) {
+    fn run(
+        &mut self,
+        (mut transforms, mut paddles, input, horizontal_input, rules, time): Self::SystemData,
+    ) {
+        let physics = &rules.paddle;
+        let delta = time.delta_seconds();
         // iterate over entities that have *both* a `Paddle` and `Transform` component
         // `par_join` can be used to join in parallel, but it is not worth doing here
-        for (paddle, transform) in (&paddles, &mut transforms).join() {
-            let movement = match paddle.side {
-                Side::Left => input.axis_value("left_paddle"),
-                Side::Right => input.axis_value("right_paddle"),
-            };
-            if let Some(mv_amount) = movement {
-                let scaled_amount = 1.2 * mv_amount as f32;
-                let paddle_y = transform.translation().y;
-                transform.set_translation_y(
-                    (paddle_y + scaled_amount)
-                        // clamp to top
-                        .min(ARENA_HEIGHT - PADDLE_HEIGHT * 0.5)
-                        // clamp to bottom
-                        .max(PADDLE_HEIGHT * 0.5),
-                );
+        for (paddle, transform) in (&mut paddles, &mut transforms).join() {
+            paddle.velocity = accelerate(paddle.velocity, input.get(paddle.side), physics, delta);
+            let paddle_y = transform.translation().y + paddle.velocity * delta;
+            // keep the paddle between the bottom and the top of the arena
+            let (bottom, top) = (paddle.height * 0.5, ARENA_HEIGHT - paddle.height * 0.5);
+            if paddle_y < bottom || paddle_y > top {
+                paddle.velocity = 0.0;
+            }
+            if bottom <= top {
+                transform.set_translation_y(paddle_y.clamp(bottom, top));
+            } else {
+                // a paddle taller than the arena sticks out of both ends evenly
+                transform.set_translation_y(ARENA_HEIGHT * 0.5);
+            }
+
+            if !physics.horizontal {
+                paddle.velocity_x = 0.0;
+                continue;
             }
+            let axis = horizontal_input.get(paddle.side);
+            paddle.velocity_x = accelerate(paddle.velocity_x, axis, physics, delta);
+            let paddle_x = transform.translation().x + paddle.velocity_x * delta;
+            // and inside its own half
+            let (left, right) = paddle.zone();
+            if paddle_x < left || paddle_x > right {
+                paddle.velocity_x = 0.0;
+            }
+            // not `clamp`, which panics on the bounds of a paddle whose width is NaN
+            transform.set_translation_x(paddle_x.max(left).min(right));
         }
     }
 }
+
+// the velocity after `delta` seconds of pushing the paddle with `axis`
+fn accelerate(velocity: f32, axis: f32, physics: &PaddlePhysics, delta: f32) -> f32 {
+    let acceleration = physics.acceleration * axis;
+    if physics.friction > 0.0 {
+        // the velocity approaches the top speed for the input exponentially, which stays
+        // stable however long the frame is
+        let top_speed = acceleration / physics.friction;
+        top_speed + (velocity - top_speed) * (-physics.friction * delta).exp()
+    } else {
+        velocity + acceleration * delta
+    }
+}
@@ -0,0 +1,197 @@
+use amethyst::{
+    core::Transform,
+    derive::SystemDesc,
+    ecs::{Join, Read, ReadStorage, System, SystemData, Write},
+    input::{InputHandler, StringBindings},
+};
+use rand::Rng;
+
+use crate::{
+    bot::{BotBall, BotPaddle, BotRequest, Bots},
+    controls::{
+        AbilityInput, AbilityInputs, Controller, Controllers, Difficulty, GameRng,
+        HorizontalInputs, PaddleInputs,
+    },
+    pong::{Ball, Paddle, ScoreBoard, Side, ARENA_HEIGHT, ARENA_WIDTH},
+};
+
+// distance from the target at which the computer moves at full speed, in arena units
+const AI_FULL_SPEED_DISTANCE: f32 = 4.0;
+// the computer stops this close to its target, so it does not jitter around it
+const AI_DEAD_ZONE: f32 = 1.0;
+
+// the computer's state for a single paddle
+#[derive(Default)]
+struct AiState {
+    // whether a ball was flying towards the paddle last frame
+    approaching: bool,
+    // offset of the aim from the ball, chosen anew for every approach
+    aim_offset: f32,
+}
+
+// fills `PaddleInputs`, `HorizontalInputs` and `AbilityInputs` from the controller of each
+// paddle, bots are asked every tick, only players on the keyboard move sideways or use abilities
+#[derive(Default, SystemDesc)]
+pub struct PaddleInputSystem {
+    left: AiState,
+    right: AiState,
+}
+
+impl<'s> System<'s> for PaddleInputSystem {
+    type SystemData = (
+        Read<'s, Controllers>,
+        Read<'s, InputHandler<StringBindings>>,
+        ReadStorage<'s, Paddle>,
+        ReadStorage<'s, Ball>,
+        ReadStorage<'s, Transform>,
+        Write<'s, GameRng>,
+        Write<'s, PaddleInputs>,
+        Write<'s, HorizontalInputs>,
+        Write<'s, AbilityInputs>,
+        Write<'s, Bots>,
+        Read<'s, ScoreBoard>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            controllers,
+            input,
+            paddles,
+            balls,
+            transforms,
+            mut rng,
+            mut inputs,
+            mut horizontal_inputs,
+            mut ability_inputs,
+            mut bots,
+            scores,
+        ): Self::SystemData,
+    ) {
+        bots.tick += 1;
+        for (paddle, transform) in (&paddles, &transforms).join() {
+            let (value, horizontal, abilities) = match controllers.get(paddle.side) {
+                Controller::Keyboard => {
+                    let (axis, horizontal, dash, smash) = match paddle.side {
+                        Side::Left => (
+                            "left_paddle",
+                            "left_paddle_horizontal",
+                            "left_dash",
+                            "left_smash",
+                        ),
+                        Side::Right => (
+                            "right_paddle",
+                            "right_paddle_horizontal",
+                            "right_dash",
+                            "right_smash",
+                        ),
+                    };
+                    let abilities = AbilityInput {
+                        dash: input.action_is_down(dash).unwrap_or(false),
+                        smash: input.action_is_down(smash).unwrap_or(false),
+                    };
+                    (
+                        input.axis_value(axis).unwrap_or(0.0),
+                        input.axis_value(horizontal).unwrap_or(0.0),
+                        abilities,
+                    )
+                }
+                Controller::Ai(difficulty) => {
+                    let state = match paddle.side {
+                        Side::Left => &mut self.left,
+                        Side::Right => &mut self.right,
+                    };
+                    let balls = (&balls, &transforms).join().map(|(ball, transform)| {
+                        (ball, transform.translation().x, transform.translation().y)
+                    });
+                    let axis = ai_axis(
+                        state,
+                        difficulty,
+                        paddle.side,
+                        transform.translation().y,
+                        balls,
+                        &mut rng,
+                    );
+                    (axis, 0.0, AbilityInput::default())
+                }
+                Controller::External => continue,
+                Controller::Bot => {
+                    let request = BotRequest {
+                        tick: bots.tick,
+                        side: paddle.side,
+                        arena: [ARENA_WIDTH, ARENA_HEIGHT],
+                        balls: (&balls, &transforms)
+                            .join()
+                            .map(|(ball, transform)| BotBall {
+                                x: transform.translation().x,
+                                y: transform.translation().y,
+                                vx: ball.velocity[0],
+                                vy: ball.velocity[1],
+                            })
+                            .collect(),
+                        paddles: (&paddles, &transforms)
+                            .join()
+                            .map(|(paddle, transform)| BotPaddle {
+                                side: paddle.side,
+                                x: transform.translation().x,
+                                y: transform.translation().y,
+                                width: paddle.width,
+                                height: paddle.height,
+                            })
+                            .collect(),
+                        score_left: scores.score_left,
+                        score_right: scores.score_right,
+                    };
+                    let timeout = bots.timeout;
+                    // without a connection the paddle stays idle
+                    let axis = bots
+                        .get_mut(paddle.side)
+                        .map_or(0.0, |bot| bot.axis(&request, timeout));
+                    (axis, 0.0, AbilityInput::default())
+                }
+            };
+            inputs.set(paddle.side, value);
+            horizontal_inputs.set(paddle.side, horizontal);
+            ability_inputs.set(paddle.side, abilities);
+        }
+    }
+}
+
+// follows the closest ball flying towards the paddle, and returns to the middle otherwise
+fn ai_axis<'a>(
+    state: &mut AiState,
+    difficulty: Difficulty,
+    side: Side,
+    paddle_y: f32,
+    balls: impl Iterator<Item = (&'a Ball, f32, f32)>,
+    rng: &mut GameRng,
+) -> f32 {
+    let approaching = balls
+        .filter(|(ball, _, _)| match side {
+            Side::Left => ball.velocity[0] < 0.0,
+            Side::Right => ball.velocity[0] > 0.0,
+        })
+        .min_by(|(_, a, _), (_, b, _)| match side {
+            Side::Left => a.total_cmp(b),
+            Side::Right => b.total_cmp(a),
+        });
+
+    let target = match approaching {
+        Some((_, _, ball_y)) => {
+            if !state.approaching {
+                let error = difficulty.aim_error();
+                state.aim_offset = rng.0.gen_range(-error..=error);
+            }
+            ball_y + state.aim_offset
+        }
+        None => ARENA_HEIGHT * 0.5,
+    };
+    state.approaching = approaching.is_some();
+
+    let distance = target - paddle_y;
+    if distance.abs() < AI_DEAD_ZONE {
+        0.0
+    } else {
+        (distance / AI_FULL_SPEED_DISTANCE).clamp(-1.0, 1.0) * difficulty.speed()
+    }
+}
@@ -0,0 +1,199 @@
+use amethyst::{
+    core::{timing::Time, Transform},
+    derive::SystemDesc,
+    ecs::{
+        Component, DenseVecStorage, Entities, Join, Read, ReadExpect, ReadStorage, System,
+        SystemData, WriteStorage,
+    },
+    renderer::{resources::Tint, SpriteRender, Transparent},
+    shrev::{EventChannel, ReaderId},
+};
+use rand::Rng;
+
+use crate::{
+    events::GameEvent,
+    pong::{Ball, RunState, Side},
+    settings::Settings,
+    theme::{SpriteRole, ThemeAssets, Themed},
+};
+
+// how long particles spawned by gameplay events live, in seconds
+const BURST_LIFETIME: f32 = 0.4;
+const BURST_SPEED: f32 = 40.0;
+// time between two trail particles, in seconds
+const TRAIL_INTERVAL: f32 = 0.02;
+
+// short-lived sprite that moves in a straight line while fading out
+pub struct Particle {
+    pub velocity: [f32; 2],
+    pub lifetime: f32,
+    pub age: f32,
+    // scale of the sprite when the particle is spawned, shrinks to zero over its lifetime
+    pub scale: f32,
+}
+
+impl Component for Particle {
+    type Storage = DenseVecStorage<Self>;
+}
+
+// spawns particles on gameplay events and trails behind the balls, then animates them
+#[derive(SystemDesc)]
+#[system_desc(name(ParticleSystemDesc))]
+pub struct ParticleSystem {
+    #[system_desc(event_channel_reader)]
+    reader_id: ReaderId<GameEvent>,
+    // time since the last trail particle was spawned
+    #[system_desc(skip)]
+    trail_timer: f32,
+}
+
+impl ParticleSystem {
+    pub fn new(reader_id: ReaderId<GameEvent>) -> Self {
+        Self {
+            reader_id,
+            trail_timer: 0.0,
+        }
+    }
+}
+
+impl<'s> System<'s> for ParticleSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Particle>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Tint>,
+        WriteStorage<'s, Transparent>,
+        WriteStorage<'s, Themed>,
+        ReadStorage<'s, Ball>,
+        Read<'s, EventChannel<GameEvent>>,
+        Read<'s, Time>,
+        Read<'s, RunState>,
+        ReadExpect<'s, Settings>,
+        Option<Read<'s, ThemeAssets>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut particles,
+            mut transforms,
+            mut sprites,
+            mut tints,
+            mut transparents,
+            mut themed,
+            balls,
+            events,
+            time,
+            run_state,
+            settings,
+            theme,
+        ): Self::SystemData,
+    ) {
+        // always drain the channel, so no stale events are replayed after a pause
+        let events = events
+            .read(&mut self.reader_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        if *run_state != RunState::Running {
+            return;
+        }
+        let settings = &settings.particles;
+        let delta = time.delta_seconds();
+
+        // particles use the active theme's sprite
+        if let (true, Some(theme)) = (settings.enabled, theme) {
+            let mut rng = rand::thread_rng();
+            // particles to spawn this frame
+            let mut spawned = Vec::new();
+
+            for event in events {
+                let (position, count, direction) = match event {
+                    GameEvent::WallBounce { position, .. } => {
+                        (position, settings.wall_bounce_count, None)
+                    }
+                    // particles fly back into the arena, away from the paddle
+                    GameEvent::PaddleHit { side, position, .. } => (
+                        position,
+                        settings.paddle_hit_count,
+                        Some(if side == Side::Left { 1.0 } else { -1.0 }),
+                    ),
+                    GameEvent::Goal { scorer, position } => (
+                        position,
+                        settings.goal_count,
+                        Some(if scorer == Side::Left { -1.0 } else { 1.0 }),
+                    ),
+                };
+
+                for _ in 0..count {
+                    let speed = BURST_SPEED * rng.gen_range(0.5..1.0);
+                    let mut velocity = [rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)];
+                    if let Some(direction) = direction {
+                        velocity[0] = f32::abs(velocity[0]) * direction;
+                    }
+                    let length = f32::hypot(velocity[0], velocity[1]).max(0.001);
+                    let particle = Particle {
+                        velocity: [velocity[0] / length * speed, velocity[1] / length * speed],
+                        lifetime: BURST_LIFETIME * rng.gen_range(0.5..1.0),
+                        age: 0.0,
+                        scale: 0.5,
+                    };
+                    spawned.push((position, particle));
+                }
+            }
+
+            if settings.trail_length > 0 {
+                self.trail_timer += delta;
+                if self.trail_timer >= TRAIL_INTERVAL {
+                    self.trail_timer = 0.0;
+                    for (_, transform) in (&balls, &transforms).join() {
+                        // the trail is as long as the number of particles alive at once
+                        let particle = Particle {
+                            velocity: [0.0, 0.0],
+                            lifetime: TRAIL_INTERVAL * settings.trail_length as f32,
+                            age: 0.0,
+                            scale: 0.8,
+                        };
+                        let position = [transform.translation().x, transform.translation().y];
+                        spawned.push((position, particle));
+                    }
+                }
+            }
+
+            for (position, particle) in spawned {
+                let mut transform = Transform::default();
+                // slightly behind the paddles and the ball
+                transform.set_translation_xyz(position[0], position[1], -0.1);
+                transform.set_scale([particle.scale, particle.scale, 1.0].into());
+                entities
+                    .build_entity()
+                    .with(particle, &mut particles)
+                    .with(transform, &mut transforms)
+                    .with(theme.sprite(SpriteRole::Particle), &mut sprites)
+                    .with(theme.tint(SpriteRole::Particle), &mut tints)
+                    .with(Themed::new(SpriteRole::Particle), &mut themed)
+                    .with(Transparent, &mut transparents)
+                    .build();
+            }
+        }
+
+        // move, shrink and fade every particle, removing the ones that expired
+        for (entity, particle, transform, tint) in
+            (&entities, &mut particles, &mut transforms, &mut tints).join()
+        {
+            particle.age += delta;
+            if particle.age >= particle.lifetime {
+                entities.delete(entity).expect("Failed to delete particle");
+                continue;
+            }
+
+            let remaining = 1.0 - particle.age / particle.lifetime;
+            transform.prepend_translation_x(particle.velocity[0] * delta);
+            transform.prepend_translation_y(particle.velocity[1] * delta);
+            let scale = particle.scale * remaining;
+            transform.set_scale([scale, scale, 1.0].into());
+            tint.0.alpha = remaining;
+        }
+    }
+}
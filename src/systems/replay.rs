@@ -0,0 +1,67 @@
+use amethyst::{
+    core::{timing::Time, Transform},
+    derive::SystemDesc,
+    ecs::{Join, Read, ReadStorage, System, SystemData, Write},
+};
+
+use crate::{
+    pong::{Ball, MatchStats, Paddle, ScoreBoard},
+    replay::{Replay, ReplayFrame, REPLAY_INTERVAL},
+};
+
+// records the running match into the `Replay` resource
+#[derive(Default, SystemDesc)]
+pub struct ReplayRecorderSystem {
+    // seconds since the recording started
+    time: f32,
+    // time of the last recorded frame
+    last_frame: Option<f32>,
+}
+
+impl<'s> System<'s> for ReplayRecorderSystem {
+    type SystemData = (
+        ReadStorage<'s, Paddle>,
+        ReadStorage<'s, Ball>,
+        ReadStorage<'s, Transform>,
+        Read<'s, ScoreBoard>,
+        Read<'s, MatchStats>,
+        Read<'s, Time>,
+        Write<'s, Replay>,
+    );
+
+    fn run(
+        &mut self,
+        (paddles, balls, transforms, scores, stats, time, mut replay): Self::SystemData,
+    ) {
+        // a new match starts a new recording
+        if replay.frames.is_empty() {
+            self.time = 0.0;
+            self.last_frame = None;
+        } else {
+            self.time += time.delta_seconds();
+        }
+        if self
+            .last_frame
+            .is_some_and(|last| self.time - last < REPLAY_INTERVAL)
+        {
+            return;
+        }
+        self.last_frame = Some(self.time);
+
+        let position =
+            |transform: &Transform| [transform.translation().x, transform.translation().y];
+        replay.frames.push(ReplayFrame {
+            time: self.time,
+            paddles: (&paddles, &transforms)
+                .join()
+                .map(|(paddle, transform)| (paddle.clone(), position(transform)))
+                .collect(),
+            balls: (&balls, &transforms)
+                .join()
+                .map(|(ball, transform)| (ball.clone(), position(transform)))
+                .collect(),
+            scores: scores.clone(),
+            stats: stats.clone(),
+        });
+    }
+}
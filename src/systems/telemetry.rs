@@ -0,0 +1,118 @@
+use amethyst::{
+    core::{timing::Time, Transform},
+    derive::SystemDesc,
+    ecs::{Join, Read, ReadStorage, System, SystemData, Write},
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::{
+    controls::PaddleInputs,
+    events::GameEvent,
+    pong::{Paddle, RunState, ScoreBoard},
+    telemetry::{Telemetry, TelemetryEvent},
+};
+
+// seconds between two samples of the paddle inputs
+const INPUT_SAMPLE_INTERVAL: f32 = 0.1;
+
+// turns gameplay events and paddle inputs into telemetry records
+#[derive(SystemDesc)]
+#[system_desc(name(TelemetrySystemDesc))]
+pub struct TelemetrySystem {
+    #[system_desc(event_channel_reader)]
+    reader_id: ReaderId<GameEvent>,
+    // time since the inputs were last sampled
+    #[system_desc(skip)]
+    sample_timer: f32,
+}
+
+impl TelemetrySystem {
+    fn new(reader_id: ReaderId<GameEvent>) -> Self {
+        Self {
+            reader_id,
+            sample_timer: 0.0,
+        }
+    }
+}
+
+impl<'s> System<'s> for TelemetrySystem {
+    type SystemData = (
+        Read<'s, EventChannel<GameEvent>>,
+        Write<'s, Telemetry>,
+        Read<'s, PaddleInputs>,
+        ReadStorage<'s, Paddle>,
+        ReadStorage<'s, Transform>,
+        Read<'s, ScoreBoard>,
+        Read<'s, RunState>,
+        Read<'s, Time>,
+    );
+
+    fn run(
+        &mut self,
+        (events, mut telemetry, inputs, paddles, transforms, scores, run_state, time): Self::SystemData,
+    ) {
+        // events are read even while disabled, so old ones are never recorded
+        let events = events.read(&mut self.reader_id);
+        if !telemetry.is_enabled() || *run_state != RunState::Running {
+            return;
+        }
+
+        telemetry.time += time.delta_seconds();
+
+        for event in events {
+            let record = match *event {
+                GameEvent::PaddleHit {
+                    side,
+                    position: [x, y],
+                    speed,
+                    offset,
+                } => {
+                    telemetry.rally += 1;
+                    TelemetryEvent::PaddleHit {
+                        side,
+                        x,
+                        y,
+                        offset,
+                        speed,
+                    }
+                }
+                GameEvent::WallBounce {
+                    position: [x, y],
+                    speed,
+                } => TelemetryEvent::WallBounce { x, y, speed },
+                GameEvent::Goal {
+                    scorer,
+                    position: [x, y],
+                } => {
+                    let length = telemetry.rally;
+                    telemetry.record(TelemetryEvent::Rally { length });
+                    telemetry.rally = 0;
+                    TelemetryEvent::Goal {
+                        side: scorer,
+                        x,
+                        y,
+                        score_left: scores.score_left,
+                        score_right: scores.score_right,
+                    }
+                }
+            };
+            let is_goal = matches!(record, TelemetryEvent::Goal { .. });
+            telemetry.record(record);
+            if is_goal {
+                telemetry.flush();
+            }
+        }
+
+        self.sample_timer += time.delta_seconds();
+        if self.sample_timer >= INPUT_SAMPLE_INTERVAL {
+            self.sample_timer -= INPUT_SAMPLE_INTERVAL;
+            for (paddle, transform) in (&paddles, &transforms).join() {
+                telemetry.record(TelemetryEvent::Input {
+                    side: paddle.side,
+                    axis: inputs.get(paddle.side),
+                    y: transform.translation().y,
+                });
+            }
+        }
+    }
+}
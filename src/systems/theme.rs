@@ -0,0 +1,131 @@
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    core::{timing::Time, Transform},
+    derive::SystemDesc,
+    ecs::{Join, Read, ReadExpect, ReadStorage, System, SystemData, Write, WriteStorage},
+    input::{InputHandler, StringBindings},
+    renderer::{resources::Tint, SpriteRender, SpriteSheet, Texture},
+    ui::{FontAsset, UiText},
+};
+use log::{info, warn};
+use std::time::SystemTime;
+
+use crate::{
+    settings::Settings,
+    theme::{Theme, ThemeAssets, Themed},
+};
+
+// seconds between two checks of whether the active theme's file changed on disk
+const RELOAD_CHECK_INTERVAL: f32 = 1.0;
+
+// switches to the theme chosen in the settings, cycles through the available themes when the
+// `cycle_theme` action is pressed, and reloads the active theme when its file is edited
+#[derive(Default, SystemDesc)]
+pub struct ThemeSystem {
+    // whether the action was held down last frame, to react to presses only once
+    was_pressed: bool,
+    // real seconds since the theme file was last checked
+    check_timer: f32,
+    // theme that could not be loaded and the modification time of its file back then,
+    // it is not retried until either of them changes
+    failed: Option<(String, Option<SystemTime>)>,
+}
+
+impl<'s> System<'s> for ThemeSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Read<'s, InputHandler<StringBindings>>,
+        Read<'s, Time>,
+        Write<'s, Settings>,
+        Option<Write<'s, ThemeAssets>>,
+        ReadExpect<'s, Loader>,
+        Read<'s, AssetStorage<Texture>>,
+        Read<'s, AssetStorage<SpriteSheet>>,
+        Read<'s, AssetStorage<FontAsset>>,
+        ReadStorage<'s, Themed>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Tint>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, UiText>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            input,
+            time,
+            mut settings,
+            assets,
+            loader,
+            textures,
+            sprite_sheets,
+            fonts,
+            themed,
+            mut sprites,
+            mut tints,
+            mut transforms,
+            mut ui_texts,
+        ): Self::SystemData,
+    ) {
+        // the first theme is loaded by the first state
+        let mut assets = match assets {
+            Some(assets) => assets,
+            None => return,
+        };
+
+        let pressed = input.action_is_down("cycle_theme").unwrap_or(false);
+        if pressed && !self.was_pressed {
+            let themes = Theme::available();
+            if !themes.is_empty() {
+                let next = themes
+                    .iter()
+                    .position(|name| *name == settings.display.theme)
+                    .map_or(0, |index| (index + 1) % themes.len());
+                settings.display.theme = themes[next].clone();
+                settings.save();
+            }
+        }
+        self.was_pressed = pressed;
+
+        let name = settings.display.theme.clone();
+        let mut reload = name != assets.name;
+        self.check_timer += time.delta_real_seconds();
+        if self.check_timer >= RELOAD_CHECK_INTERVAL {
+            self.check_timer = 0.0;
+            reload |= Theme::modified(&name) != assets.modified;
+        }
+        if !reload || self.failed == Some((name.clone(), Theme::modified(&name))) {
+            return;
+        }
+
+        let loaded = Theme::load_named(&name).and_then(|theme| {
+            ThemeAssets::load(&name, theme, &loader, &textures, &sprite_sheets, &fonts)
+        });
+        match loaded {
+            Ok(loaded) => {
+                info!("Switched to theme {}", name);
+                *assets = loaded;
+                self.failed = None;
+            }
+            Err(e) => {
+                warn!("{}, keeping theme {}", e, assets.name);
+                self.failed = Some((name.clone(), Theme::modified(&name)));
+                return;
+            }
+        }
+
+        for (themed, sprite, tint) in (&themed, &mut sprites, &mut tints).join() {
+            *sprite = assets.sprite(themed.role);
+            *tint = assets.tint(themed.role);
+        }
+        for (themed, transform) in (&themed, &mut transforms).join() {
+            if let Some(size) = themed.size {
+                transform.set_scale(assets.scale(themed.role, size));
+            }
+        }
+        for text in (&mut ui_texts).join() {
+            text.color = assets.theme.text_color;
+            text.font = assets.font.clone();
+        }
+    }
+}
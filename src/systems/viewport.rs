@@ -0,0 +1,90 @@
+use amethyst::{
+    derive::SystemDesc,
+    ecs::{
+        Component, DenseVecStorage, Join, ReadExpect, ReadStorage, System, SystemData, WriteStorage,
+    },
+    renderer::Camera,
+    ui::{UiText, UiTransform},
+    window::ScreenDimensions,
+};
+
+use crate::pong::{ARENA_HEIGHT, ARENA_WIDTH};
+
+// pixels per arena unit the hand-written UI layouts were made for, a 500x500 window
+const REFERENCE_SCALE: f32 = 5.0;
+
+// layout of a UI element at the reference window size, scaled to the actual window each frame
+pub struct ScaledUi {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub font_size: f32,
+}
+
+impl ScaledUi {
+    // remembers the current layout of `transform` as the reference one
+    pub fn new(transform: &UiTransform, font_size: f32) -> Self {
+        Self {
+            x: transform.local_x,
+            y: transform.local_y,
+            width: transform.width,
+            height: transform.height,
+            font_size,
+        }
+    }
+}
+
+impl Component for ScaledUi {
+    type Storage = DenseVecStorage<Self>;
+}
+
+// fits the whole arena into the window without stretching it, leaving letterbox bars on the
+// sides that do not match its aspect ratio, and scales the UI along with it
+#[derive(SystemDesc)]
+pub struct ViewportSystem;
+
+impl<'s> System<'s> for ViewportSystem {
+    type SystemData = (
+        ReadExpect<'s, ScreenDimensions>,
+        WriteStorage<'s, Camera>,
+        ReadStorage<'s, ScaledUi>,
+        WriteStorage<'s, UiTransform>,
+        WriteStorage<'s, UiText>,
+    );
+
+    fn run(
+        &mut self,
+        (dimensions, mut cameras, layouts, mut ui_transforms, mut ui_texts): Self::SystemData,
+    ) {
+        let (width, height) = (dimensions.width(), dimensions.height());
+        if width <= 0.0 || height <= 0.0 {
+            // the window is minimised
+            return;
+        }
+
+        // pixels per arena unit, the arena touches the window on at least one axis
+        let scale = (width / ARENA_WIDTH).min(height / ARENA_HEIGHT);
+        // size of a single letterbox bar in pixels, at most one of these is non-zero
+        let bar_x = (width - ARENA_WIDTH * scale) * 0.5;
+        let bar_y = (height - ARENA_HEIGHT * scale) * 0.5;
+
+        // the camera shows the arena and the bars around it
+        for camera in (&mut cameras).join() {
+            *camera = Camera::standard_2d(width / scale, height / scale);
+        }
+
+        let ui_scale = scale / REFERENCE_SCALE;
+        for (layout, transform) in (&layouts, &mut ui_transforms).join() {
+            // keep elements anchored to the arena's edges instead of the window's
+            let (anchor_x, anchor_y) = transform.anchor.norm_offset();
+            transform.local_x = layout.x * ui_scale - anchor_x * 2.0 * bar_x;
+            transform.local_y = layout.y * ui_scale - anchor_y * 2.0 * bar_y;
+            transform.width = layout.width * ui_scale;
+            transform.height = layout.height * ui_scale;
+        }
+        for (layout, text) in (&layouts, &mut ui_texts).join() {
+            text.font_size = layout.font_size * ui_scale;
+        }
+    }
+}
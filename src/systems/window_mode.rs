@@ -0,0 +1,72 @@
+use amethyst::{
+    derive::SystemDesc,
+    ecs::{Read, ReadExpect, System, SystemData, Write},
+    input::{InputHandler, StringBindings},
+    window::Window,
+    winit::dpi::{LogicalPosition, LogicalSize},
+};
+
+use crate::settings::{Settings, WindowMode};
+
+// applies the window mode from the settings whenever it changes, and cycles through the
+// modes when the `cycle_window_mode` action is pressed
+#[derive(Default, SystemDesc)]
+pub struct WindowModeSystem {
+    // mode the window is currently in, `None` before the first frame
+    applied: Option<WindowMode>,
+    // size of the window before leaving windowed mode, restored when coming back
+    windowed_size: Option<LogicalSize>,
+    // whether the action was held down last frame, to react to presses only once
+    was_pressed: bool,
+}
+
+impl<'s> System<'s> for WindowModeSystem {
+    type SystemData = (
+        ReadExpect<'s, Window>,
+        Write<'s, Settings>,
+        Read<'s, InputHandler<StringBindings>>,
+    );
+
+    fn run(&mut self, (window, mut settings, input): Self::SystemData) {
+        let pressed = input.action_is_down("cycle_window_mode").unwrap_or(false);
+        if pressed && !self.was_pressed {
+            settings.display.mode = settings.display.mode.next();
+            settings.save();
+        }
+        self.was_pressed = pressed;
+
+        let mode = settings.display.mode;
+        if self.applied == Some(mode) {
+            return;
+        }
+
+        if matches!(self.applied, None | Some(WindowMode::Windowed)) {
+            self.windowed_size = window.get_inner_size();
+        }
+
+        let monitor = window.get_current_monitor();
+        match mode {
+            WindowMode::Windowed => {
+                window.set_fullscreen(None);
+                window.set_decorations(true);
+                if let Some(size) = self.windowed_size {
+                    window.set_inner_size(size);
+                }
+            }
+            // a window without decorations covering the whole monitor
+            WindowMode::Borderless => {
+                window.set_fullscreen(None);
+                window.set_decorations(false);
+                let hidpi_factor = monitor.get_hidpi_factor();
+                let position = monitor.get_position();
+                let size = monitor.get_dimensions();
+                window.set_position(LogicalPosition::from_physical(position, hidpi_factor));
+                window.set_inner_size(LogicalSize::from_physical(size, hidpi_factor));
+            }
+            WindowMode::Fullscreen => {
+                window.set_fullscreen(Some(monitor));
+            }
+        }
+        self.applied = Some(mode);
+    }
+}
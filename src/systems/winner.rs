@@ -1,15 +1,16 @@
 use amethyst::{
-    assets::AssetStorage,
-    audio::{output::Output, Source},
     core::transform::Transform,
     derive::SystemDesc,
-    ecs::{Join, Read, ReadExpect, System, SystemData, Write, WriteStorage},
-    ui::UiText,
+    ecs::{Join, Read, System, SystemData, Write, WriteStorage},
+    shrev::EventChannel,
 };
+use log::info;
 
 use crate::{
-    audio::{play_score_sound, Sounds},
-    pong::{Ball, ScoreBoard, ScoreText, ARENA_HEIGHT, ARENA_WIDTH},
+    events::GameEvent,
+    pong::{Ball, ScoreBoard, Side, ARENA_HEIGHT, ARENA_WIDTH, BALL_VELOCITY_X, BALL_VELOCITY_Y},
+    rules::Rules,
+    sounds::{SoundEvent, SoundParams, SoundPlayer},
 };
 
 #[derive(SystemDesc)]
@@ -19,67 +20,67 @@ impl<'s> System<'s> for WinnerSystem {
     type SystemData = (
         WriteStorage<'s, Ball>,
         WriteStorage<'s, Transform>,
-        // stores all `UiText` components
-        WriteStorage<'s, UiText>,
         // single required resource
         Write<'s, ScoreBoard>,
-        // single required resource, panic if not available
-        ReadExpect<'s, ScoreText>,
-        Read<'s, AssetStorage<Source>>,
-        ReadExpect<'s, Sounds>,
-        Option<Read<'s, Output>>,
+        SoundPlayer<'s>,
+        Write<'s, EventChannel<GameEvent>>,
+        Read<'s, Rules>,
     );
 
     fn run(
         &mut self,
-        (
-            mut balls,
-            mut transforms,
-            mut ui_text,
-            mut scores,
-            score_text,
-            storage,
-            sounds,
-            audio_output,
-        ): Self::SystemData,
+        (mut balls, mut transforms, mut scores, sounds, mut events, rules): Self::SystemData,
     ) {
         for (ball, transform) in (&mut balls, &mut transforms).join() {
             let ball_x = transform.translation().x;
+            let ball_y = transform.translation().y;
 
-            let did_hit = if ball_x <= ball.radius {
+            let scorer = if ball_x <= ball.radius {
                 // right player scored on the left side
-                println!("Player 2 scores!");
+                info!("Player 2 scores!");
 
                 // maximum score is 999 to avoid text overlap
                 scores.score_right = (scores.score_right + 1).min(999);
-                // update the UI, correct element is queried by its `Entity`
-                if let Some(text) = ui_text.get_mut(score_text.p2_score) {
-                    text.text = scores.score_right.to_string();
-                }
-                true
+                Some(Side::Right)
             } else if ball_x >= ARENA_WIDTH - ball.radius {
                 // left player scored on the right side
-                println!("Player 1 scores!");
+                info!("Player 1 scores!");
 
                 scores.score_left = (scores.score_left + 1).min(999);
-                if let Some(text) = ui_text.get_mut(score_text.p1_score) {
-                    text.text = scores.score_left.to_string();
-                }
-                true
+                Some(Side::Left)
             } else {
-                false
+                None
             };
 
-            if did_hit {
-                // reverse direction
-                ball.velocity[0] = -ball.velocity[0];
+            if let Some(scorer) = scorer {
+                events.single_write(GameEvent::Goal {
+                    scorer,
+                    position: [ball_x, ball_y],
+                });
+
+                // panned towards the goal the ball went into
+                let params = SoundParams::for_ball(ball_x, ball.velocity);
+                sounds.play(SoundEvent::Goal, params);
+
+                let scorer_score = match scorer {
+                    Side::Left => scores.score_left,
+                    Side::Right => scores.score_right,
+                };
+                if scorer_score == rules.winning_score - 1 {
+                    sounds.play(SoundEvent::MatchPoint, params);
+                }
+
+                // reverse direction, at serve speed whatever spin added during the rally
+                ball.velocity = [
+                    -BALL_VELOCITY_X.copysign(ball.velocity[0]),
+                    BALL_VELOCITY_Y.copysign(ball.velocity[1]),
+                ];
+                ball.spin = 0.0;
                 // reset position
                 transform.set_translation_x(ARENA_WIDTH * 0.5);
                 transform.set_translation_y(ARENA_HEIGHT * 0.5);
 
-                play_score_sound(&*sounds, &storage, audio_output.as_deref());
-
-                println!(
+                info!(
                     "Score: | {:^3} | {:^3} |",
                     scores.score_left, scores.score_right
                 );
@@ -0,0 +1,221 @@
+use amethyst::Error;
+use log::warn;
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::pong::Side;
+
+// something that happened during a match, written as one record of the telemetry file
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TelemetryEvent {
+    MatchStart,
+    // `offset` as in `GameEvent::PaddleHit`
+    PaddleHit {
+        side: Side,
+        x: f32,
+        y: f32,
+        offset: f32,
+        speed: f32,
+    },
+    WallBounce {
+        x: f32,
+        y: f32,
+        speed: f32,
+    },
+    Goal {
+        side: Side,
+        x: f32,
+        y: f32,
+        score_left: i32,
+        score_right: i32,
+    },
+    // number of paddle hits before a goal ended the rally
+    Rally {
+        length: u32,
+    },
+    // axis value and position of a paddle, sampled at a fixed interval
+    Input {
+        side: Side,
+        axis: f32,
+        y: f32,
+    },
+}
+
+// columns of the CSV format, every event fills the ones it has values for
+const CSV_HEADER: [&str; 11] = [
+    "match", "time", "event", "side", "x", "y", "offset", "speed", "axis", "length", "score",
+];
+
+impl TelemetryEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            TelemetryEvent::MatchStart => "match_start",
+            TelemetryEvent::PaddleHit { .. } => "paddle_hit",
+            TelemetryEvent::WallBounce { .. } => "wall_bounce",
+            TelemetryEvent::Goal { .. } => "goal",
+            TelemetryEvent::Rally { .. } => "rally",
+            TelemetryEvent::Input { .. } => "input",
+        }
+    }
+
+    // values of every CSV column after `match`, `time` and `event`, empty where not applicable
+    fn csv_columns(&self) -> [String; 8] {
+        let side = |side: &Side| format!("{:?}", side);
+        let mut columns: [String; 8] = Default::default();
+        match self {
+            TelemetryEvent::MatchStart => {}
+            TelemetryEvent::PaddleHit {
+                side: hit_side,
+                x,
+                y,
+                offset,
+                speed,
+            } => {
+                columns[0] = side(hit_side);
+                columns[1] = x.to_string();
+                columns[2] = y.to_string();
+                columns[3] = offset.to_string();
+                columns[4] = speed.to_string();
+            }
+            TelemetryEvent::WallBounce { x, y, speed } => {
+                columns[1] = x.to_string();
+                columns[2] = y.to_string();
+                columns[4] = speed.to_string();
+            }
+            TelemetryEvent::Goal {
+                side: scorer,
+                x,
+                y,
+                score_left,
+                score_right,
+            } => {
+                columns[0] = side(scorer);
+                columns[1] = x.to_string();
+                columns[2] = y.to_string();
+                columns[7] = format!("{}-{}", score_left, score_right);
+            }
+            TelemetryEvent::Rally { length } => columns[6] = length.to_string(),
+            TelemetryEvent::Input {
+                side: input_side,
+                axis,
+                y,
+            } => {
+                columns[0] = side(input_side);
+                columns[2] = y.to_string();
+                columns[5] = axis.to_string();
+            }
+        }
+        columns
+    }
+}
+
+// a single line of the JSON Lines format
+#[derive(Serialize)]
+struct JsonRecord<'a> {
+    #[serde(rename = "match")]
+    match_number: u32,
+    time: f32,
+    #[serde(flatten)]
+    event: &'a TelemetryEvent,
+}
+
+enum TelemetrySink {
+    JsonLines(BufWriter<File>),
+    Csv(Box<csv::Writer<File>>),
+}
+
+// writes telemetry records of every match to a file, as a `Resource`, does nothing unless a file
+// was given with `--telemetry`
+#[derive(Default)]
+pub struct Telemetry {
+    sink: Option<TelemetrySink>,
+    // matches recorded so far, the first one is `1`
+    match_number: u32,
+    // seconds since the current match started, pauses excluded
+    pub time: f32,
+    // paddle hits since the last goal
+    pub rally: u32,
+}
+
+impl Telemetry {
+    // files ending in `.csv` are written as CSV, anything else as JSON Lines
+    pub fn create(path: &Path) -> Result<Self, Error> {
+        let file = File::create(path).map_err(|e| {
+            Error::from_string(format!("Could not create telemetry file {:?}: {}", path, e))
+        })?;
+        let is_csv = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+        let sink = if is_csv {
+            let mut writer = csv::Writer::from_writer(file);
+            writer
+                .write_record(CSV_HEADER)
+                .map_err(|e| Error::from_string(e.to_string()))?;
+            TelemetrySink::Csv(Box::new(writer))
+        } else {
+            TelemetrySink::JsonLines(BufWriter::new(file))
+        };
+        Ok(Self {
+            sink: Some(sink),
+            ..Default::default()
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.sink.is_some()
+    }
+
+    pub fn start_match(&mut self) {
+        self.match_number += 1;
+        self.time = 0.0;
+        self.rally = 0;
+        self.record(TelemetryEvent::MatchStart);
+    }
+
+    pub fn record(&mut self, event: TelemetryEvent) {
+        let (match_number, time) = (self.match_number, self.time);
+        let result = match &mut self.sink {
+            None => return,
+            Some(TelemetrySink::JsonLines(writer)) => {
+                let record = JsonRecord {
+                    match_number,
+                    time,
+                    event: &event,
+                };
+                serde_json::to_writer(&mut *writer, &record)
+                    .map_err(|e| e.to_string())
+                    .and_then(|_| writeln!(writer).map_err(|e| e.to_string()))
+            }
+            Some(TelemetrySink::Csv(writer)) => {
+                let mut row = vec![
+                    match_number.to_string(),
+                    time.to_string(),
+                    event.name().to_string(),
+                ];
+                row.extend(event.csv_columns());
+                writer.write_record(&row).map_err(|e| e.to_string())
+            }
+        };
+        if let Err(e) = result {
+            warn!("Could not write telemetry, disabling it: {}", e);
+            self.sink = None;
+        }
+    }
+
+    // writes buffered records to the file, so little is lost if the game is killed
+    pub fn flush(&mut self) {
+        let result = match &mut self.sink {
+            None => Ok(()),
+            Some(TelemetrySink::JsonLines(writer)) => writer.flush(),
+            Some(TelemetrySink::Csv(writer)) => writer.flush(),
+        };
+        if let Err(e) = result {
+            warn!("Could not write telemetry: {}", e);
+        }
+    }
+}
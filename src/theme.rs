@@ -0,0 +1,288 @@
+use amethyst::{
+    assets::{AssetStorage, Handle, Loader},
+    config::Config,
+    core::{math::Vector3, Transform},
+    ecs::{Component, DenseVecStorage},
+    prelude::*,
+    renderer::{
+        palette::Srgba, resources::Tint, ImageFormat, Sprite, SpriteRender, SpriteSheet, Texture,
+    },
+    ui::{FontAsset, FontHandle, TtfFormat},
+    utils::application_root_dir,
+    Error,
+};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::PathBuf, time::SystemTime};
+
+use crate::settings::Settings;
+
+// themes are `.ron` files inside `assets/themes`, named after their file
+const THEMES_DIR: &str = "themes";
+// used when the theme chosen in the settings cannot be loaded
+pub const DEFAULT_THEME: &str = "classic";
+
+// what a sprite is used for in the game, themes decide which sprite plays which role
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SpriteRole {
+    Paddle,
+    Ball,
+    Particle,
+    // solid sprite filling the arena's background
+    Background,
+    // the bars covering everything outside of the arena, drawn with the background's sprite
+    Letterbox,
+}
+
+// a single sprite inside the theme's sprite sheet, in pixels
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpriteDefinition {
+    pub name: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+// contents of a theme file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Theme {
+    // texture of the sprite sheet, relative to `assets`
+    pub sprite_sheet: String,
+    pub texture_width: u32,
+    pub texture_height: u32,
+    pub sprites: Vec<SpriteDefinition>,
+    // name of the sprite used for each role
+    pub roles: HashMap<SpriteRole, String>,
+    // colour multiplied onto the sprites of a role, white if missing
+    #[serde(default)]
+    pub tints: HashMap<SpriteRole, [f32; 4]>,
+    pub clear_color: [f32; 4],
+    // colour of the bars around the arena, black if missing
+    #[serde(default = "default_letterbox_color")]
+    pub letterbox_color: [f32; 4],
+    // font of every text, relative to `assets`
+    pub font: String,
+    pub text_color: [f32; 4],
+}
+
+fn default_letterbox_color() -> [f32; 4] {
+    [0.0, 0.0, 0.0, 1.0]
+}
+
+impl Theme {
+    fn path(name: &str) -> Result<PathBuf, Error> {
+        Ok(application_root_dir()?
+            .join("assets")
+            .join(THEMES_DIR)
+            .join(format!("{}.ron", name)))
+    }
+
+    pub fn load_named(name: &str) -> Result<Self, Error> {
+        let path = Self::path(name)?;
+        Self::load(&path)
+            .map_err(|e| Error::from_string(format!("Could not load theme {:?}: {}", path, e)))
+    }
+
+    // names of every theme inside the themes directory, in alphabetical order
+    pub fn available() -> Vec<String> {
+        let dir = match application_root_dir() {
+            Ok(root) => root.join("assets").join(THEMES_DIR),
+            Err(_) => return Vec::new(),
+        };
+        let mut names = fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("ron"))
+                    .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    // last time the theme file was written, used to reload it when it changes on disk
+    pub fn modified(name: &str) -> Option<SystemTime> {
+        fs::metadata(Self::path(name).ok()?).ok()?.modified().ok()
+    }
+}
+
+// `Resource` holding the loaded assets of the active theme
+pub struct ThemeAssets {
+    pub name: String,
+    pub theme: Theme,
+    // modification time of the theme file when it was loaded, to detect changes
+    pub modified: Option<SystemTime>,
+    sheet: Handle<SpriteSheet>,
+    sprite_indices: HashMap<SpriteRole, usize>,
+    pub font: FontHandle,
+}
+
+impl ThemeAssets {
+    // loads the textures, sprites and font of a theme, fails if a role refers to a missing sprite
+    pub fn load(
+        name: &str,
+        theme: Theme,
+        loader: &Loader,
+        textures: &AssetStorage<Texture>,
+        sprite_sheets: &AssetStorage<SpriteSheet>,
+        fonts: &AssetStorage<FontAsset>,
+    ) -> Result<Self, Error> {
+        let mut sprite_indices = HashMap::new();
+        for role in [
+            SpriteRole::Paddle,
+            SpriteRole::Ball,
+            SpriteRole::Particle,
+            SpriteRole::Background,
+        ]
+        .iter()
+        {
+            let sprite_name = theme.roles.get(role).ok_or_else(|| {
+                Error::from_string(format!("Theme {} has no sprite for {:?}", name, role))
+            })?;
+            let index = theme
+                .sprites
+                .iter()
+                .position(|sprite| &sprite.name == sprite_name)
+                .ok_or_else(|| {
+                    Error::from_string(format!(
+                        "Theme {} uses unknown sprite {:?} for {:?}",
+                        name, sprite_name, role
+                    ))
+                })?;
+            sprite_indices.insert(*role, index);
+        }
+        sprite_indices.insert(
+            SpriteRole::Letterbox,
+            sprite_indices[&SpriteRole::Background],
+        );
+
+        let texture = loader.load(
+            theme.sprite_sheet.as_str(),
+            ImageFormat::default(),
+            (),
+            textures,
+        );
+        let sprites = theme
+            .sprites
+            .iter()
+            .map(|sprite| {
+                Sprite::from_pixel_values(
+                    theme.texture_width,
+                    theme.texture_height,
+                    sprite.width,
+                    sprite.height,
+                    sprite.x,
+                    sprite.y,
+                    [0.0, 0.0],
+                    false,
+                    false,
+                )
+            })
+            .collect();
+        let sheet = loader.load_from_data(SpriteSheet { texture, sprites }, (), sprite_sheets);
+        let font = loader.load(theme.font.as_str(), TtfFormat, (), fonts);
+
+        Ok(Self {
+            name: name.to_string(),
+            theme,
+            modified: Theme::modified(name),
+            sheet,
+            sprite_indices,
+            font,
+        })
+    }
+
+    pub fn sprite(&self, role: SpriteRole) -> SpriteRender {
+        SpriteRender::new(self.sheet.clone(), self.sprite_indices[&role])
+    }
+
+    // scale that makes the sprite of `role` exactly `size` large, as sprites are as large in
+    // arena units as in pixels
+    pub fn scale(&self, role: SpriteRole, size: [f32; 2]) -> Vector3<f32> {
+        let sprite = &self.theme.sprites[self.sprite_indices[&role]];
+        Vector3::new(
+            size[0] / sprite.width as f32,
+            size[1] / sprite.height as f32,
+            1.0,
+        )
+    }
+
+    pub fn tint(&self, role: SpriteRole) -> Tint {
+        let [r, g, b, a] = match role {
+            // the background blends in with the window
+            SpriteRole::Background => self.theme.clear_color,
+            SpriteRole::Letterbox => self.theme.letterbox_color,
+            _ => self
+                .theme
+                .tints
+                .get(&role)
+                .copied()
+                .unwrap_or([1.0, 1.0, 1.0, 1.0]),
+        };
+        Tint(Srgba::new(r, g, b, a))
+    }
+}
+
+// marks an entity whose sprite and tint are chosen by the active theme
+pub struct Themed {
+    pub role: SpriteRole,
+    // size of the entity in arena units, its sprite is scaled to fit regardless of the theme
+    pub size: Option<[f32; 2]>,
+}
+
+impl Themed {
+    pub fn new(role: SpriteRole) -> Self {
+        Self { role, size: None }
+    }
+}
+
+impl Component for Themed {
+    type Storage = DenseVecStorage<Self>;
+}
+
+// components of an entity drawn according to the active theme, `size` as in `Themed`
+pub fn themed_sprite(
+    world: &World,
+    role: SpriteRole,
+    size: Option<[f32; 2]>,
+    mut transform: Transform,
+) -> (SpriteRender, Tint, Transform, Themed) {
+    let theme = world.read_resource::<ThemeAssets>();
+    if let Some(size) = size {
+        transform.set_scale(theme.scale(role, size));
+    }
+    (
+        theme.sprite(role),
+        theme.tint(role),
+        transform,
+        Themed { role, size },
+    )
+}
+
+// loads the theme chosen in the settings, unless one is already loaded
+pub fn initialise_theme(world: &mut World) {
+    if world.has_value::<ThemeAssets>() {
+        return;
+    }
+
+    let name = world.read_resource::<Settings>().display.theme.clone();
+    let assets = load_theme_assets(world, &name).or_else(|e| {
+        warn!("{}, falling back to {}", e, DEFAULT_THEME);
+        load_theme_assets(world, DEFAULT_THEME)
+    });
+    world.insert(assets.expect("Failed to load the default theme"));
+}
+
+pub fn load_theme_assets(world: &World, name: &str) -> Result<ThemeAssets, Error> {
+    ThemeAssets::load(
+        name,
+        Theme::load_named(name)?,
+        &world.read_resource(),
+        &world.read_resource(),
+        &world.read_resource(),
+        &world.read_resource(),
+    )
+}
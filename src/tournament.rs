@@ -0,0 +1,257 @@
+use amethyst::Error;
+use serde::Serialize;
+use std::{collections::BTreeMap, fs, path::Path};
+
+use crate::{
+    bot::Bots,
+    controls::Controllers,
+    pong::Side,
+    rules::Rules,
+    simulation::{Simulation, TICK},
+};
+
+// result of a single match of a tournament
+#[derive(Clone, Debug, Serialize)]
+pub struct MatchResult {
+    pub seed: u64,
+    // `None` if nobody won within the step limit
+    pub winner: Option<Side>,
+    pub score_left: i32,
+    pub score_right: i32,
+    // average paddle hits per rally
+    pub average_rally: f32,
+    pub seconds: f32,
+}
+
+// statistics of a series of headless matches between the same two controllers
+#[derive(Clone, Debug, Serialize)]
+pub struct TournamentReport {
+    pub left: String,
+    pub right: String,
+    pub left_wins: u32,
+    pub right_wins: u32,
+    // matches nobody won within the step limit
+    pub unfinished: u32,
+    pub left_win_rate: f32,
+    pub right_win_rate: f32,
+    // average paddle hits per rally over all matches
+    pub average_rally: f32,
+    // number of matches ending with each final score, written as `left-right`
+    pub scores: BTreeMap<String, u32>,
+    pub matches: Vec<MatchResult>,
+}
+
+// plays `matches` matches as fast as possible, the n-th one seeded with `seed + n`, so the same
+// arguments always give the same report
+pub fn run_tournament(
+    names: [String; 2],
+    rules: &Rules,
+    controllers: Controllers,
+    mut bots: Bots,
+    matches: u32,
+    seed: u64,
+    max_ticks: u64,
+) -> TournamentReport {
+    let mut results = Vec::new();
+    let mut rallies = Vec::new();
+    for index in 0..matches {
+        let match_seed = seed.wrapping_add(index as u64);
+        let mut simulation = Simulation::new(rules.clone(), controllers, Some(match_seed));
+        // bots stay connected from one match to the next
+        simulation.world.insert(bots);
+        let winner = simulation.run(max_ticks);
+        bots = simulation.world.remove::<Bots>().unwrap_or_default();
+
+        let scores = simulation.scores();
+        rallies.extend_from_slice(simulation.rallies());
+        results.push(MatchResult {
+            seed: match_seed,
+            winner,
+            score_left: scores.score_left,
+            score_right: scores.score_right,
+            average_rally: average(simulation.rallies()),
+            seconds: simulation.ticks() as f32 * TICK,
+        });
+    }
+    TournamentReport::new(names, results, &rallies)
+}
+
+fn average(values: &[u32]) -> f32 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<u32>() as f32 / values.len() as f32
+    }
+}
+
+impl TournamentReport {
+    // statistics of the matches played, `rallies` holds the hits of every rally of them
+    fn new(names: [String; 2], results: Vec<MatchResult>, rallies: &[u32]) -> Self {
+        let matches = results.len() as u32;
+        let wins = |side: Side| {
+            results
+                .iter()
+                .filter(|result| result.winner == Some(side))
+                .count() as u32
+        };
+        let (left_wins, right_wins) = (wins(Side::Left), wins(Side::Right));
+        let rate = |wins: u32| {
+            if matches == 0 {
+                0.0
+            } else {
+                wins as f32 / matches as f32
+            }
+        };
+        let mut scores = BTreeMap::new();
+        for result in &results {
+            *scores
+                .entry(format!("{}-{}", result.score_left, result.score_right))
+                .or_insert(0) += 1;
+        }
+
+        let [left, right] = names;
+        TournamentReport {
+            left,
+            right,
+            left_wins,
+            right_wins,
+            unfinished: matches - left_wins - right_wins,
+            left_win_rate: rate(left_wins),
+            right_win_rate: rate(right_wins),
+            average_rally: average(rallies),
+            scores,
+            matches: results,
+        }
+    }
+
+    // human readable summary
+    pub fn table(&self) -> String {
+        let matches = self.matches.len();
+        let width = self.left.len().max(self.right.len()).max(6);
+        let mut table = format!(
+            "{:<width$} | {:>5} | {:>7}\n",
+            "Player",
+            "Wins",
+            "Rate",
+            width = width
+        );
+        table += &format!("{}\n", "-".repeat(width + 18));
+        for (name, wins, rate) in [
+            (&self.left, self.left_wins, self.left_win_rate),
+            (&self.right, self.right_wins, self.right_win_rate),
+        ] {
+            table += &format!(
+                "{:<width$} | {:>5} | {:>6.1}%\n",
+                name,
+                wins,
+                rate * 100.0,
+                width = width
+            );
+        }
+        table += &format!(
+            "\n{} matches, {} unfinished, {:.2} hits per rally on average\n",
+            matches, self.unfinished, self.average_rally
+        );
+
+        // most frequent scores first
+        let mut scores = self.scores.iter().collect::<Vec<_>>();
+        scores.sort_by(|(a_score, a_count), (b_score, b_count)| {
+            b_count.cmp(a_count).then(a_score.cmp(b_score))
+        });
+        table += "\nScore   | Matches\n";
+        for (score, count) in scores {
+            table += &format!("{:<7} | {:>7}\n", score, count);
+        }
+        table
+    }
+
+    // writes the full report, including every match, as JSON
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let json =
+            serde_json::to_string_pretty(self).map_err(|e| Error::from_string(e.to_string()))?;
+        fs::write(path, json).map_err(|e| {
+            Error::from_string(format!(
+                "Could not write tournament report {:?}: {}",
+                path, e
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(winner: Option<Side>, score_left: i32, score_right: i32) -> MatchResult {
+        MatchResult {
+            seed: 0,
+            winner,
+            score_left,
+            score_right,
+            average_rally: 0.0,
+            seconds: 0.0,
+        }
+    }
+
+    fn report() -> TournamentReport {
+        TournamentReport::new(
+            ["Alice".to_string(), "Bob".to_string()],
+            vec![
+                result(Some(Side::Right), 3, 11),
+                result(Some(Side::Left), 11, 5),
+                result(None, 4, 4),
+                result(Some(Side::Left), 11, 5),
+            ],
+            &[2, 4, 6, 8],
+        )
+    }
+
+    #[test]
+    fn wins_rates_and_scores_are_counted() {
+        let report = report();
+        assert_eq!((report.left_wins, report.right_wins), (2, 1));
+        assert_eq!(report.unfinished, 1);
+        assert_eq!(report.left_win_rate, 0.5);
+        assert_eq!(report.right_win_rate, 0.25);
+        assert_eq!(report.average_rally, 5.0);
+        assert_eq!(report.scores["11-5"], 2);
+        assert_eq!(report.scores["3-11"], 1);
+        assert_eq!(report.scores["4-4"], 1);
+        assert_eq!(report.matches.len(), 4);
+    }
+
+    #[test]
+    fn empty_tournaments_have_no_rates() {
+        let report = TournamentReport::new(["A".to_string(), "B".to_string()], Vec::new(), &[]);
+        assert_eq!(report.left_win_rate, 0.0);
+        assert_eq!(report.right_win_rate, 0.0);
+        assert_eq!(report.average_rally, 0.0);
+        assert!(report.table().contains("0 matches, 0 unfinished"));
+    }
+
+    #[test]
+    fn table_lists_players_then_the_most_frequent_scores() {
+        let table = report().table();
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[..4],
+            [
+                "Player |  Wins |    Rate",
+                "------------------------",
+                "Alice  |     2 |   50.0%",
+                "Bob    |     1 |   25.0%",
+            ]
+        );
+        assert!(table.contains("4 matches, 1 unfinished, 5.00 hits per rally on average"));
+        let scores = &lines[lines.len() - 4..];
+        assert_eq!(
+            scores,
+            [
+                "Score   | Matches",
+                "11-5    |       2",
+                "3-11    |       1",
+                "4-4     |       1",
+            ]
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use amethyst::{
+    ecs::Entity,
+    prelude::*,
+    ui::{Anchor, FontHandle, LineMode, UiText, UiTransform},
+};
+
+use crate::{systems::ScaledUi, theme::ThemeAssets};
+
+// font of the active theme
+pub fn load_font(world: &World) -> FontHandle {
+    world.read_resource::<ThemeAssets>().font.clone()
+}
+
+// text colour of the active theme
+pub fn text_color(world: &World) -> [f32; 4] {
+    world.read_resource::<ThemeAssets>().theme.text_color
+}
+
+// creates a single line of text centered horizontally, `y` is measured from the top of the screen
+pub fn create_label(
+    world: &mut World,
+    font: FontHandle,
+    id: &str,
+    y: f32,
+    font_size: f32,
+    text: String,
+) -> Entity {
+    let transform = UiTransform::new(
+        id.to_string(),
+        Anchor::TopMiddle,
+        Anchor::TopMiddle,
+        0.,
+        y,
+        1.,
+        500.,
+        font_size,
+    );
+
+    let color = text_color(world);
+    world
+        .create_entity()
+        .with(ScaledUi::new(&transform, font_size))
+        .with(transform)
+        .with(UiText::new(
+            font,
+            text,
+            color,
+            font_size,
+            LineMode::Single,
+            Anchor::Middle,
+        ))
+        .build()
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use amethyst_pong_tutorial::{
+    pong::{Side, ARENA_HEIGHT, ARENA_WIDTH, PADDLE_WIDTH},
+    Paddle,
+};
+use common::Harness;
+use proptest::prelude::*;
+
+// rounding errors allowed in positions
+const EPSILON: f32 = 1e-3;
+
+// frame times from very fast to very slow machines
+fn frame_times() -> impl Strategy<Value = Vec<f32>> {
+    prop::collection::vec(
+        prop_oneof![0.000_01f32..0.001, 0.001f32..0.05, 0.05f32..0.5],
+        1..120,
+    )
+}
+
+fn speed() -> impl Strategy<Value = f32> {
+    prop_oneof![-300.0f32..-1.0, 1.0f32..300.0]
+}
+
+proptest! {
+    #[test]
+    fn ball_never_leaves_the_arena_vertically(
+        y in 0.0f32..1.0,
+        velocity in (speed(), speed()),
+        radius in 0.5f32..5.0,
+        frame_times in frame_times(),
+    ) {
+        let mut harness = Harness::new();
+        let y = radius + y * (ARENA_HEIGHT - radius * 2.0);
+        let ball = harness.ball([50.0, y], [velocity.0, velocity.1], radius);
+
+        for delta in frame_times {
+            harness.tick_with(delta, 0.0, 0.0);
+            let [_, y] = harness.position(ball);
+            prop_assert!(
+                y >= radius - EPSILON && y <= ARENA_HEIGHT - radius + EPSILON,
+                "ball at y = {} with radius {}", y, radius
+            );
+        }
+    }
+
+    #[test]
+    fn ball_never_passes_through_a_paddle(
+        side in prop_oneof![Just(Side::Left), Just(Side::Right)],
+        start_x in 10.0f32..90.0,
+        ball_y in 10.0f32..90.0,
+        // how far from the paddle's centre the ball flies, within the paddle and its radius
+        offset in -0.99f32..0.99,
+        speed in 1.0f32..300.0,
+        frame_times in frame_times(),
+    ) {
+        let mut harness = Harness::new();
+        let paddle = Paddle::new(side);
+        let reach = paddle.height * 0.5 + 2.0;
+        let paddle_y = (ball_y - offset * reach).clamp(paddle.height * 0.5, ARENA_HEIGHT - paddle.height * 0.5);
+        harness.paddle(side, paddle_y);
+        // flies straight at the paddle
+        let velocity = match side {
+            Side::Left => [-speed, 0.0],
+            Side::Right => [speed, 0.0],
+        };
+        let ball = harness.ball([start_x, ball_y], velocity, 2.0);
+        let hits = (ball_y - paddle_y).abs() <= reach;
+
+        for delta in frame_times {
+            harness.tick_with(delta, 0.0, 0.0);
+            if !hits {
+                break;
+            }
+            // the paddle is never passed, so the other side never scores
+            let conceded = match side {
+                Side::Left => harness.scores().1,
+                Side::Right => harness.scores().0,
+            };
+            prop_assert_eq!(conceded, 0);
+            // a bounce so fast it reaches the other goal serves the ball again
+            if harness.scores() != (0, 0) {
+                break;
+            }
+            let [x, _] = harness.position(ball);
+            let behind = match side {
+                Side::Left => x < PADDLE_WIDTH,
+                Side::Right => x > ARENA_WIDTH - PADDLE_WIDTH,
+            };
+            prop_assert!(!behind, "ball at x = {} got behind the paddle", x);
+            // once bounced, the ball is taken out of the test before it reaches the other side
+            let bounced = match side {
+                Side::Left => harness.velocity(ball)[0] > 0.0,
+                Side::Right => harness.velocity(ball)[0] < 0.0,
+            };
+            if bounced {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn a_paddle_as_high_as_the_arena_catches_every_ball(
+        start_x in 10.0f32..90.0,
+        start_y in 0.05f32..0.95,
+        velocity in (1.0f32..300.0, speed()),
+        frame_times in frame_times(),
+    ) {
+        let mut harness = Harness::new();
+        let wall = Paddle {
+            height: ARENA_HEIGHT,
+            ..Paddle::new(Side::Left)
+        };
+        harness.custom_paddle(wall, ARENA_HEIGHT * 0.5);
+        let ball = harness.ball([start_x, start_y * ARENA_HEIGHT], [-velocity.0, velocity.1], 2.0);
+
+        for delta in frame_times {
+            harness.tick_with(delta, 0.0, 0.0);
+            prop_assert_eq!(harness.scores().1, 0, "the ball went past the paddle");
+            if harness.velocity(ball)[0] > 0.0 {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn a_goal_is_scored_exactly_once_per_crossing(
+        start_x in 10.0f32..90.0,
+        start_y in 10.0f32..90.0,
+        velocity in (speed(), speed()),
+        frame_times in frame_times(),
+    ) {
+        let mut harness = Harness::new();
+        let ball = harness.ball([start_x, start_y], [velocity.0, velocity.1], 2.0);
+
+        let mut scores = harness.scores();
+        for delta in frame_times {
+            harness.tick_with(delta, 0.0, 0.0);
+            let new_scores = harness.scores();
+            let goals = (new_scores.0 - scores.0) + (new_scores.1 - scores.1);
+            prop_assert!(goals <= 1, "{} goals in a single frame", goals);
+
+            let [x, y] = harness.position(ball);
+            if goals == 1 {
+                // served again from the middle
+                prop_assert!((x - ARENA_WIDTH * 0.5).abs() < EPSILON);
+                prop_assert!((y - ARENA_HEIGHT * 0.5).abs() < EPSILON);
+            } else {
+                // a ball that is not served again never stays inside a goal
+                prop_assert!(x > 2.0 - EPSILON && x < ARENA_WIDTH - 2.0 + EPSILON, "ball at x = {}", x);
+            }
+            scores = new_scores;
+        }
+    }
+}
@@ -0,0 +1,161 @@
+// scripts matches frame by frame on a world without window or audio, built the way other projects
+// embed the game: through `PongBundle`
+#![allow(dead_code)]
+
+use amethyst::{
+    core::{bundle::SystemBundle, Time, Transform},
+    ecs::{Dispatcher, DispatcherBuilder, Entity},
+    prelude::*,
+};
+use amethyst_pong_tutorial::{
+    controls::{
+        AbilityInput, AbilityInputs, Controller, Controllers, HorizontalInputs, PaddleInputs,
+    },
+    pong::{create_ball, create_paddle, match_winner, MatchStats, RunState, Side, ARENA_WIDTH},
+    rules::Rules,
+    simulation::TICK,
+    Ball, Paddle, PongBundle, ScoreBoard,
+};
+
+pub struct Harness {
+    pub world: World,
+    dispatcher: Dispatcher<'static, 'static>,
+}
+
+impl Harness {
+    // an arena without paddles or balls, both paddles are moved by the scripted inputs
+    pub fn new() -> Self {
+        Self::with_rules(Rules::default())
+    }
+
+    pub fn with_rules(rules: Rules) -> Self {
+        let mut world = World::new();
+        let mut builder = DispatcherBuilder::new();
+        PongBundle::new()
+            .without_audio()
+            .without_telemetry()
+            .build(&mut world, &mut builder)
+            .expect("Failed to build the gameplay systems");
+        let mut dispatcher = builder.build();
+        dispatcher.setup(&mut world);
+
+        world.insert(rules);
+        world.insert(Controllers {
+            left: Controller::External,
+            right: Controller::External,
+        });
+        world.insert(ScoreBoard::default());
+        world.insert(MatchStats::default());
+        world.insert(RunState::Running);
+        Self { world, dispatcher }
+    }
+
+    pub fn set_run_state(&mut self, run_state: RunState) {
+        *self.world.write_resource() = run_state;
+    }
+
+    // a standard paddle at its usual horizontal position
+    pub fn paddle(&mut self, side: Side, y: f32) -> Entity {
+        self.custom_paddle(Paddle::new(side), y)
+    }
+
+    pub fn custom_paddle(&mut self, paddle: Paddle, y: f32) -> Entity {
+        let x = match paddle.side {
+            Side::Left => paddle.width * 0.5,
+            Side::Right => ARENA_WIDTH - paddle.width * 0.5,
+        };
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(x, y, 0.0);
+        create_paddle(&mut self.world, paddle, transform)
+    }
+
+    pub fn ball(&mut self, position: [f32; 2], velocity: [f32; 2], radius: f32) -> Entity {
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(position[0], position[1], 0.0);
+        create_ball(
+            &mut self.world,
+            Ball {
+                velocity,
+                radius,
+                spin: 0.0,
+            },
+            transform,
+        )
+    }
+
+    pub fn set_spin(&mut self, ball: Entity, spin: f32) {
+        self.world
+            .write_storage::<Ball>()
+            .get_mut(ball)
+            .expect("Entity is no ball")
+            .spin = spin;
+    }
+
+    // holds the sideways axes of both paddles until they are set again
+    pub fn set_horizontal(&mut self, left: f32, right: f32) {
+        *self.world.write_resource::<HorizontalInputs>() = HorizontalInputs { left, right };
+    }
+
+    // holds the ability buttons of a paddle until they are set again
+    pub fn set_abilities(&mut self, side: Side, dash: bool, smash: bool) {
+        self.world
+            .write_resource::<AbilityInputs>()
+            .set(side, AbilityInput { dash, smash });
+    }
+
+    // advances the match by one fixed step with the given paddle axis values
+    pub fn tick(&mut self, left: f32, right: f32) {
+        self.tick_with(TICK, left, right);
+    }
+
+    // advances the match by one step of `delta` seconds
+    pub fn tick_with(&mut self, delta: f32, left: f32, right: f32) {
+        *self.world.write_resource::<PaddleInputs>() = PaddleInputs { left, right };
+        self.world.write_resource::<Time>().set_delta_seconds(delta);
+        self.dispatcher.dispatch(&self.world);
+        self.world.maintain();
+    }
+
+    pub fn run(&mut self, ticks: u32, left: f32, right: f32) {
+        for _ in 0..ticks {
+            self.tick(left, right);
+        }
+    }
+
+    // advances until `condition` holds, returns whether it did within `max_ticks`
+    pub fn run_until(&mut self, max_ticks: u32, condition: impl Fn(&Self) -> bool) -> bool {
+        for _ in 0..max_ticks {
+            if condition(self) {
+                return true;
+            }
+            self.tick(0.0, 0.0);
+        }
+        condition(self)
+    }
+
+    pub fn position(&self, entity: Entity) -> [f32; 2] {
+        let transforms = self.world.read_storage::<Transform>();
+        let translation = transforms
+            .get(entity)
+            .expect("Entity has no transform")
+            .translation();
+        [translation.x, translation.y]
+    }
+
+    pub fn velocity(&self, ball: Entity) -> [f32; 2] {
+        self.world
+            .read_storage::<Ball>()
+            .get(ball)
+            .expect("Entity is no ball")
+            .velocity
+    }
+
+    pub fn scores(&self) -> (i32, i32) {
+        let scores = self.world.read_resource::<ScoreBoard>();
+        (scores.score_left, scores.score_right)
+    }
+
+    pub fn winner(&self) -> Option<Side> {
+        match_winner(&self.world)
+    }
+}
@@ -0,0 +1,428 @@
+mod common;
+
+use amethyst_pong_tutorial::{
+    pong::{
+        RunState, Side, ARENA_HEIGHT, ARENA_WIDTH, BALL_RADIUS, BALL_VELOCITY_X, BALL_VELOCITY_Y,
+        MAX_SPIN_VELOCITY_Y, PADDLE_HEIGHT, PADDLE_WIDTH,
+    },
+    rules::Rules,
+    simulation::TICK,
+    Paddle,
+};
+use common::Harness;
+
+const EPSILON: f32 = 1e-3;
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < EPSILON,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn ball_moves_by_its_velocity() {
+    let mut harness = Harness::new();
+    let ball = harness.ball([50.0, 50.0], [30.0, -60.0], BALL_RADIUS);
+
+    harness.run(10, 0.0, 0.0);
+
+    let [x, y] = harness.position(ball);
+    assert_close(x, 50.0 + 30.0 * TICK * 10.0);
+    assert_close(y, 50.0 - 60.0 * TICK * 10.0);
+}
+
+#[test]
+fn ball_bounces_off_the_top_wall() {
+    let mut harness = Harness::new();
+    let ball = harness.ball([50.0, ARENA_HEIGHT - 5.0], [10.0, 60.0], BALL_RADIUS);
+
+    assert!(harness.run_until(30, |harness| harness.velocity(ball)[1] < 0.0));
+    assert_close(harness.velocity(ball)[1], -60.0);
+    assert_close(harness.velocity(ball)[0], 10.0);
+    assert!(harness.position(ball)[1] <= ARENA_HEIGHT);
+    assert_eq!(harness.scores(), (0, 0));
+}
+
+#[test]
+fn ball_bounces_off_the_bottom_wall() {
+    let mut harness = Harness::new();
+    let ball = harness.ball([50.0, 5.0], [-10.0, -60.0], BALL_RADIUS);
+
+    assert!(harness.run_until(30, |harness| harness.velocity(ball)[1] > 0.0));
+    assert_close(harness.velocity(ball)[1], 60.0);
+    assert!(harness.position(ball)[1] >= 0.0);
+}
+
+#[test]
+fn ball_bounces_off_the_left_paddle() {
+    let mut harness = Harness::new();
+    harness.paddle(Side::Left, 50.0);
+    let ball = harness.ball([20.0, 50.0], [-75.0, 0.0], BALL_RADIUS);
+
+    assert!(harness.run_until(60, |harness| harness.velocity(ball)[0] > 0.0));
+    assert_close(harness.velocity(ball)[0], 75.0);
+    assert_eq!(harness.scores(), (0, 0));
+
+    // it flies back towards the right
+    let x = harness.position(ball)[0];
+    harness.run(5, 0.0, 0.0);
+    assert!(harness.position(ball)[0] > x);
+}
+
+#[test]
+fn ball_bounces_off_the_right_paddle() {
+    let mut harness = Harness::new();
+    harness.paddle(Side::Right, 30.0);
+    let ball = harness.ball([80.0, 30.0], [75.0, 0.0], BALL_RADIUS);
+
+    assert!(harness.run_until(60, |harness| harness.velocity(ball)[0] < 0.0));
+    assert_eq!(harness.scores(), (0, 0));
+}
+
+#[test]
+fn ball_passing_a_paddle_scores_for_the_other_side() {
+    let mut harness = Harness::new();
+    // the paddle is far from where the ball crosses its line
+    harness.paddle(Side::Left, 10.0);
+    let ball = harness.ball([20.0, 80.0], [-75.0, 0.0], BALL_RADIUS);
+
+    assert!(harness.run_until(60, |harness| harness.scores() != (0, 0)));
+    assert_eq!(harness.scores(), (0, 1));
+
+    // the ball is served again from the middle, towards the player who scored
+    let [x, y] = harness.position(ball);
+    assert_close(x, ARENA_WIDTH * 0.5);
+    assert_close(y, ARENA_HEIGHT * 0.5);
+    assert!(harness.velocity(ball)[0] > 0.0);
+}
+
+#[test]
+fn left_player_scores_on_the_right_side() {
+    let mut harness = Harness::new();
+    harness.ball([ARENA_WIDTH - 10.0, 50.0], [75.0, 0.0], BALL_RADIUS);
+
+    assert!(harness.run_until(30, |harness| harness.scores() != (0, 0)));
+    assert_eq!(harness.scores(), (1, 0));
+}
+
+#[test]
+fn reaching_the_winning_score_ends_the_match() {
+    let mut harness = Harness::with_rules(Rules {
+        winning_score: 2,
+        ..Rules::default()
+    });
+    harness.ball([ARENA_WIDTH - 10.0, 50.0], [75.0, 0.0], BALL_RADIUS);
+
+    assert!(harness.run_until(30, |harness| harness.scores() == (1, 0)));
+    assert_eq!(harness.winner(), None);
+
+    // served back to the left, nobody defends
+    assert!(harness.run_until(120, |harness| harness.scores() != (1, 0)));
+    assert_eq!(harness.scores(), (1, 1));
+    assert_eq!(harness.winner(), None);
+
+    assert!(harness.run_until(120, |harness| harness.winner().is_some()));
+    assert_eq!(harness.winner(), Some(Side::Left));
+    assert_eq!(harness.scores(), (2, 1));
+}
+
+#[test]
+fn paddles_follow_their_inputs() {
+    let mut harness = Harness::new();
+    let left = harness.paddle(Side::Left, 50.0);
+    let right = harness.paddle(Side::Right, 50.0);
+
+    harness.run(10, 1.0, -0.5);
+
+    assert!(harness.position(left)[1] > 50.0);
+    assert!(harness.position(right)[1] < 50.0);
+    // full input moves twice as far as half input
+    let left_distance = harness.position(left)[1] - 50.0;
+    let right_distance = 50.0 - harness.position(right)[1];
+    assert_close(left_distance, right_distance * 2.0);
+}
+
+#[test]
+fn paddles_speed_up_and_glide_to_a_halt() {
+    let mut harness = Harness::new();
+    let paddle = harness.paddle(Side::Left, 20.0);
+
+    let step = |harness: &mut Harness, axis: f32| {
+        let y = harness.position(paddle)[1];
+        harness.tick(axis, 0.0);
+        harness.position(paddle)[1] - y
+    };
+    let first = step(&mut harness, 1.0);
+    let second = step(&mut harness, 1.0);
+    assert!(first > 0.0 && second > first);
+
+    // without input the paddle keeps moving for a moment, ever slower
+    let gliding = step(&mut harness, 0.0);
+    assert!(gliding > 0.0 && gliding < second);
+    harness.run(120, 0.0, 0.0);
+    assert!(step(&mut harness, 0.0).abs() < EPSILON);
+}
+
+#[test]
+fn a_moving_paddle_gives_the_ball_spin() {
+    let mut harness = Harness::new();
+    harness.paddle(Side::Left, 50.0);
+    let ball = harness.ball([12.0, 52.0], [-75.0, 0.0], BALL_RADIUS);
+
+    // the paddle moves up while the ball flies towards it
+    for _ in 0..10 {
+        harness.tick(1.0, 0.0);
+    }
+    assert!(harness.velocity(ball)[0] > 0.0);
+    assert!(harness.velocity(ball)[1] > 0.0);
+}
+
+#[test]
+fn the_serve_after_a_spin_hit_is_back_to_serve_speed() {
+    let mut harness = Harness::new();
+    harness.paddle(Side::Left, 50.0);
+    let ball = harness.ball([12.0, 52.0], [-75.0, 0.0], BALL_RADIUS);
+    // hit by the paddle moving up
+    for _ in 0..10 {
+        harness.tick(1.0, 0.0);
+    }
+    let [_, spun] = harness.velocity(ball);
+    assert!(spun > 0.0 && spun != BALL_VELOCITY_Y);
+
+    // the ball flies into the empty right goal
+    assert!(harness.run_until(300, |harness| harness.scores() != (0, 0)));
+    let [x, y] = harness.velocity(ball);
+    assert_close(x, -BALL_VELOCITY_X);
+    assert_close(y.abs(), BALL_VELOCITY_Y);
+}
+
+#[test]
+fn spin_curves_the_ball_when_the_rules_allow_it() {
+    let mut rules = Rules::default();
+    rules.paddle.curve = 2.0;
+    let mut harness = Harness::with_rules(rules);
+    let ball = harness.ball([50.0, 50.0], [75.0, 0.0], BALL_RADIUS);
+    harness.set_spin(ball, 10.0);
+
+    harness.run(10, 0.0, 0.0);
+    // less than without the spin wearing off
+    let velocity_y = harness.velocity(ball)[1];
+    assert!(velocity_y > 0.0 && velocity_y < 10.0 * 2.0 * TICK * 10.0);
+}
+
+#[test]
+fn spin_cannot_send_the_ball_faster_than_its_limit() {
+    let mut rules = Rules::default();
+    rules.paddle.curve = 2.0;
+    let mut harness = Harness::with_rules(rules);
+    let ball = harness.ball([50.0, 50.0], [0.0, 0.0], BALL_RADIUS);
+    harness.set_spin(ball, 1000.0);
+
+    harness.run(5, 0.0, 0.0);
+    assert_close(harness.velocity(ball)[1], MAX_SPIN_VELOCITY_Y);
+}
+
+#[test]
+fn paddles_move_sideways_only_when_the_rules_allow_it() {
+    let mut harness = Harness::new();
+    let paddle = harness.paddle(Side::Left, 50.0);
+    harness.set_horizontal(1.0, 0.0);
+    harness.run(30, 0.0, 0.0);
+    assert_close(harness.position(paddle)[0], PADDLE_WIDTH * 0.5);
+
+    let mut rules = Rules::default();
+    rules.paddle.horizontal = true;
+    let mut harness = Harness::with_rules(rules);
+    let left = harness.paddle(Side::Left, 50.0);
+    let right = harness.paddle(Side::Right, 50.0);
+    harness.set_horizontal(1.0, -1.0);
+    harness.run(300, 0.0, 0.0);
+
+    // each paddle stays inside its own half
+    assert_close(
+        harness.position(left)[0],
+        (ARENA_WIDTH - PADDLE_WIDTH) * 0.5,
+    );
+    assert_close(
+        harness.position(right)[0],
+        (ARENA_WIDTH + PADDLE_WIDTH) * 0.5,
+    );
+}
+
+#[test]
+fn a_paddle_hits_the_ball_from_any_side() {
+    let mut rules = Rules::default();
+    rules.paddle.horizontal = true;
+
+    // from behind, pushing a slower ball forward
+    let mut harness = Harness::with_rules(rules.clone());
+    let paddle = harness.paddle(Side::Left, 50.0);
+    let ball = harness.ball([12.0, 50.0], [10.0, 0.0], BALL_RADIUS);
+    harness.set_horizontal(1.0, 0.0);
+    assert!(harness.run_until(60, |harness| harness.velocity(ball)[0] > 10.0));
+    assert!(harness.position(ball)[0] > harness.position(paddle)[0]);
+
+    // from below, sending the ball up
+    let mut harness = Harness::with_rules(rules);
+    let paddle = harness.paddle(Side::Left, 20.0);
+    harness.set_horizontal(1.0, 0.0);
+    harness.run(20, 0.0, 0.0);
+    harness.set_horizontal(0.0, 0.0);
+    harness.run(120, 0.0, 0.0);
+    let x = harness.position(paddle)[0];
+    let ball = harness.ball([x, 40.0], [0.0, -30.0], BALL_RADIUS);
+    assert!(harness.run_until(60, |harness| harness.velocity(ball)[1] > 0.0));
+    assert_close(harness.velocity(ball)[1], 30.0);
+    assert!(harness.position(ball)[1] > harness.position(paddle)[1] + PADDLE_HEIGHT * 0.5);
+    assert_eq!(harness.scores(), (0, 0));
+}
+
+#[test]
+fn paddles_on_their_goal_line_return_the_ball_off_their_front() {
+    let mut harness = Harness::new();
+    let paddle = harness.paddle(Side::Left, 50.0);
+    // dropping onto the top of the paddle
+    let ball = harness.ball([PADDLE_WIDTH, 70.0], [-5.0, -60.0], BALL_RADIUS);
+
+    assert!(harness.run_until(60, |harness| harness.velocity(ball)[0] > 0.0));
+    assert!(harness.velocity(ball)[1] < 0.0);
+    assert!(harness.position(ball)[0] >= PADDLE_WIDTH + BALL_RADIUS);
+    assert!(harness.position(ball)[1] > harness.position(paddle)[1]);
+    assert_eq!(harness.scores(), (0, 0));
+}
+
+#[test]
+fn a_dash_pushes_the_paddle_once_per_cooldown() {
+    let mut harness = Harness::new();
+    let paddle = harness.paddle(Side::Left, 20.0);
+    let walked = {
+        let mut harness = Harness::new();
+        let paddle = harness.paddle(Side::Left, 20.0);
+        harness.run(10, 1.0, 0.0);
+        harness.position(paddle)[1] - 20.0
+    };
+
+    harness.set_abilities(Side::Left, true, false);
+    harness.run(10, 1.0, 0.0);
+    let dashed = harness.position(paddle)[1] - 20.0;
+    assert!(dashed > walked * 1.5);
+
+    // pressed again right away, the dash is still cooling down
+    harness.set_abilities(Side::Left, false, false);
+    harness.tick(0.0, 0.0);
+    harness.set_abilities(Side::Left, true, false);
+    harness.run(60, 0.0, 0.0);
+    let y = harness.position(paddle)[1];
+    harness.run(10, 1.0, 0.0);
+    assert_close(harness.position(paddle)[1] - y, walked);
+}
+
+#[test]
+fn a_charged_smash_launches_the_ball_faster() {
+    let mut rules = Rules::default();
+    rules.abilities.smash_charge_time = 3.0 * TICK;
+    let mut harness = Harness::with_rules(rules.clone());
+    harness.paddle(Side::Left, 50.0);
+    let ball = harness.ball([20.0, 50.0], [-60.0, 0.0], BALL_RADIUS);
+
+    // fully charged and released just before the ball arrives
+    harness.set_abilities(Side::Left, false, true);
+    harness.run(5, 0.0, 0.0);
+    harness.set_abilities(Side::Left, false, false);
+
+    assert!(harness.run_until(20, |harness| harness.velocity(ball)[0] > 0.0));
+    assert_close(
+        harness.velocity(ball)[0],
+        60.0 * rules.abilities.smash_boost,
+    );
+}
+
+#[test]
+fn smashes_are_capped_and_do_not_outlast_the_rally() {
+    let mut rules = Rules::default();
+    rules.abilities.smash_charge_time = 3.0 * TICK;
+    let mut harness = Harness::with_rules(rules.clone());
+    harness.paddle(Side::Left, 50.0);
+    // already faster than a smashed serve
+    let ball = harness.ball([20.0, 50.0], [-120.0, 0.0], BALL_RADIUS);
+
+    harness.set_abilities(Side::Left, false, true);
+    harness.run(5, 0.0, 0.0);
+    harness.set_abilities(Side::Left, false, false);
+
+    let serve_speed = f32::hypot(BALL_VELOCITY_X, BALL_VELOCITY_Y);
+    assert!(harness.run_until(20, |harness| harness.velocity(ball)[0] > 0.0));
+    assert_close(
+        harness.velocity(ball)[0],
+        serve_speed * rules.abilities.smash_boost,
+    );
+
+    // the ball flies into the empty right goal and is served again at serve speed
+    assert!(harness.run_until(120, |harness| harness.scores() != (0, 0)));
+    let [x, y] = harness.velocity(ball);
+    assert_close(f32::hypot(x, y), serve_speed);
+}
+
+#[test]
+fn paddles_stay_inside_the_arena() {
+    let mut harness = Harness::new();
+    let left = harness.paddle(Side::Left, 50.0);
+    let right = harness.paddle(Side::Right, 50.0);
+
+    harness.run(300, 1.0, -1.0);
+
+    assert_close(
+        harness.position(left)[1],
+        ARENA_HEIGHT - PADDLE_HEIGHT * 0.5,
+    );
+    assert_close(harness.position(right)[1], PADDLE_HEIGHT * 0.5);
+
+    harness.run(300, -1.0, 1.0);
+
+    assert_close(harness.position(left)[1], PADDLE_HEIGHT * 0.5);
+    assert_close(
+        harness.position(right)[1],
+        ARENA_HEIGHT - PADDLE_HEIGHT * 0.5,
+    );
+}
+
+#[test]
+fn paddles_taller_than_the_arena_stay_in_the_middle() {
+    let mut harness = Harness::new();
+    let mut tall = Paddle::new(Side::Left);
+    tall.height = ARENA_HEIGHT * 1.2;
+    let paddle = harness.custom_paddle(tall, ARENA_HEIGHT * 0.5);
+
+    harness.run(30, 1.0, 0.0);
+    assert_close(harness.position(paddle)[1], ARENA_HEIGHT * 0.5);
+}
+
+#[test]
+fn paddles_too_wide_for_their_half_stay_on_their_goal_line() {
+    let mut rules = Rules::default();
+    rules.paddle.horizontal = true;
+    let mut harness = Harness::with_rules(rules);
+    let mut wide = Paddle::new(Side::Right);
+    wide.width = ARENA_WIDTH * 0.6;
+    let paddle = harness.custom_paddle(wide, ARENA_HEIGHT * 0.5);
+
+    harness.set_horizontal(0.0, -1.0);
+    harness.run(30, 0.0, 0.0);
+    assert_close(harness.position(paddle)[0], ARENA_WIDTH * 0.7);
+}
+
+#[test]
+fn nothing_moves_while_paused() {
+    let mut harness = Harness::new();
+    let paddle = harness.paddle(Side::Left, 50.0);
+    let ball = harness.ball([50.0, 50.0], [75.0, 50.0], BALL_RADIUS);
+    harness.set_run_state(RunState::Paused);
+
+    harness.run(10, 1.0, 0.0);
+
+    assert_eq!(harness.position(ball), [50.0, 50.0]);
+    assert_eq!(harness.position(paddle), [PADDLE_WIDTH * 0.5, 50.0]);
+}